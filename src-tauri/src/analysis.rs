@@ -0,0 +1,619 @@
+//! Whole-scan analysis queries over the scan index.
+//!
+//! These run against the full index rather than the capped UI tree, so
+//! entries folded into `__other__` nodes are still accounted for.
+
+use crate::category::{CategoryTable, FileCategory};
+use crate::index::{NodeId, ScanIndex};
+use crate::platform;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A directory counts as leaf-ish when no single subdirectory holds more
+/// than this share of its bytes (so `/`, `/Users`, `/Users/me` chains of
+/// pass-through parents don't crowd out the directories that own the data).
+const LEAFISH_MAX_CHILD_SHARE: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopEntry {
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+    pub modified_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopItems {
+    pub files: Vec<TopEntry>,
+    pub dirs: Vec<TopEntry>,
+}
+
+/// Min-heap that keeps only the `limit` largest entries seen
+struct TopN {
+    limit: usize,
+    heap: BinaryHeap<Reverse<(u64, NodeId)>>,
+}
+
+impl TopN {
+    fn new(limit: usize) -> Self {
+        Self { limit, heap: BinaryHeap::with_capacity(limit + 1) }
+    }
+
+    fn push(&mut self, size: u64, id: NodeId) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.heap.len() < self.limit {
+            self.heap.push(Reverse((size, id)));
+        } else if self.heap.peek().is_some_and(|Reverse((min, _))| size > *min) {
+            self.heap.pop();
+            self.heap.push(Reverse((size, id)));
+        }
+    }
+
+    /// Drain into entries ordered largest first
+    fn into_entries(self, index: &ScanIndex) -> Vec<TopEntry> {
+        let mut items: Vec<(u64, NodeId)> = self.heap.into_iter().map(|Reverse(e)| e).collect();
+        items.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        items
+            .into_iter()
+            .filter_map(|(size, id)| {
+                let node = index.get(id)?;
+                Some(TopEntry {
+                    path: index.path_of(id).to_string_lossy().to_string(),
+                    size,
+                    is_dir: node.is_dir,
+                    modified_at: node.modified_at,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The N largest files and N largest leaf-ish directories under `start`
+pub fn top_items(index: &ScanIndex, start: NodeId, limit: usize) -> TopItems {
+    let mut files = TopN::new(limit);
+    let mut dirs = TopN::new(limit);
+
+    for id in index.descendants(start) {
+        let Some(node) = index.get(id) else { continue };
+        if !node.is_dir {
+            files.push(node.size, id);
+            continue;
+        }
+        if node.size == 0 {
+            continue;
+        }
+
+        let largest_child_dir = node
+            .children
+            .iter()
+            .filter_map(|&c| index.get(c))
+            .filter(|c| c.is_dir)
+            .map(|c| c.size)
+            .max()
+            .unwrap_or(0);
+        if (largest_child_dir as f64) <= node.size as f64 * LEAFISH_MAX_CHILD_SHARE {
+            dirs.push(node.size, id);
+        }
+    }
+
+    TopItems {
+        files: files.into_entries(index),
+        dirs: dirs.into_entries(index),
+    }
+}
+
+/// Bytes and file count for one extension (None = no extension)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionStat {
+    pub extension: Option<String>,
+    pub bytes: u64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryStat {
+    pub category: FileCategory,
+    pub bytes: u64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionTypeStat {
+    pub extension: Option<String>,
+    pub category: FileCategory,
+    pub bytes: u64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeStats {
+    pub total_bytes: u64,
+    pub total_files: u64,
+    pub by_category: Vec<CategoryStat>,
+    pub by_extension: Vec<ExtensionTypeStat>,
+}
+
+/// Aggregate file bytes and counts per extension under `start`, largest first.
+///
+/// Kept category-agnostic so stored stats stay valid when the category table
+/// is reconfigured.
+pub fn extension_stats(index: &ScanIndex, start: NodeId) -> Vec<ExtensionStat> {
+    let mut totals: HashMap<Option<&str>, (u64, u64)> = HashMap::new();
+    for id in index.descendants(start) {
+        let Some(node) = index.get(id) else { continue };
+        if node.is_dir {
+            continue;
+        }
+        let entry = totals.entry(node.extension.as_deref()).or_default();
+        entry.0 += node.size;
+        entry.1 += 1;
+    }
+
+    let mut stats: Vec<ExtensionStat> = totals
+        .into_iter()
+        .map(|(ext, (bytes, count))| ExtensionStat {
+            extension: ext.map(str::to_string),
+            bytes,
+            count,
+        })
+        .collect();
+    stats.sort_unstable_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.extension.cmp(&b.extension)));
+    stats
+}
+
+/// Roll extension stats up into categories using the given table
+pub fn type_stats(extensions: &[ExtensionStat], table: &CategoryTable) -> TypeStats {
+    let mut by_category: HashMap<FileCategory, (u64, u64)> = HashMap::new();
+    let mut by_extension = Vec::with_capacity(extensions.len());
+    let mut total_bytes = 0;
+    let mut total_files = 0;
+
+    for stat in extensions {
+        let category = table.category_of(stat.extension.as_deref());
+        let entry = by_category.entry(category).or_default();
+        entry.0 += stat.bytes;
+        entry.1 += stat.count;
+        total_bytes += stat.bytes;
+        total_files += stat.count;
+        by_extension.push(ExtensionTypeStat {
+            extension: stat.extension.clone(),
+            category,
+            bytes: stat.bytes,
+            count: stat.count,
+        });
+    }
+
+    let mut by_category: Vec<CategoryStat> = FileCategory::ALL
+        .into_iter()
+        .filter_map(|category| {
+            let (bytes, count) = by_category.get(&category).copied()?;
+            Some(CategoryStat { category, bytes, count })
+        })
+        .collect();
+    by_category.sort_by_key(|c| Reverse(c.bytes));
+
+    TypeStats {
+        total_bytes,
+        total_files,
+        by_category,
+        by_extension,
+    }
+}
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Upper age bound (exclusive) of each histogram bucket; anything older
+/// lands in a final "older" bucket
+const AGE_BUCKETS: [(&str, u64); 5] = [
+    ("1w", 7 * DAY_SECS),
+    ("1m", 30 * DAY_SECS),
+    ("6m", 182 * DAY_SECS),
+    ("1y", 365 * DAY_SECS),
+    ("3y", 3 * 365 * DAY_SECS),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgeBucket {
+    pub label: String,
+    /// Exclusive upper bound in seconds (None for the open-ended bucket)
+    pub max_age_secs: Option<u64>,
+    pub bytes: u64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleDir {
+    pub path: String,
+    pub size: u64,
+    pub file_count: u64,
+    pub newest_modified_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgeAnalysis {
+    pub buckets: Vec<AgeBucket>,
+    /// Files without a recorded mtime
+    pub unknown_bytes: u64,
+    pub unknown_count: u64,
+    pub stale_dirs: Vec<StaleDir>,
+}
+
+/// Bucket file bytes by last-modified age and find the largest directories
+/// whose newest file is older than `stale_after_secs`.
+///
+/// Only the outermost stale directory of a stale chain is reported, so an
+/// abandoned project shows up once rather than once per subfolder.
+pub fn age_analysis(
+    index: &ScanIndex,
+    start: NodeId,
+    now: u64,
+    stale_after_secs: u64,
+    limit: usize,
+) -> AgeAnalysis {
+    let mut buckets: Vec<AgeBucket> = AGE_BUCKETS
+        .iter()
+        .map(|(label, max)| AgeBucket {
+            label: label.to_string(),
+            max_age_secs: Some(*max),
+            bytes: 0,
+            count: 0,
+        })
+        .chain(std::iter::once(AgeBucket {
+            label: "older".to_string(),
+            max_age_secs: None,
+            bytes: 0,
+            count: 0,
+        }))
+        .collect();
+    let mut unknown_bytes = 0;
+    let mut unknown_count = 0;
+
+    // Newest file mtime and file count per directory, filled bottom-up by
+    // walking the pre-order in reverse
+    let order: Vec<NodeId> = index.descendants(start).collect();
+    let mut newest: HashMap<NodeId, (Option<u64>, u64)> = HashMap::new();
+
+    for &id in order.iter().rev() {
+        let Some(node) = index.get(id) else { continue };
+
+        if !node.is_dir {
+            match node.modified_at {
+                Some(mtime) => {
+                    let age = now.saturating_sub(mtime);
+                    let slot = AGE_BUCKETS
+                        .iter()
+                        .position(|(_, max)| age < *max)
+                        .unwrap_or(AGE_BUCKETS.len());
+                    buckets[slot].bytes += node.size;
+                    buckets[slot].count += 1;
+                }
+                None => {
+                    unknown_bytes += node.size;
+                    unknown_count += 1;
+                }
+            }
+            continue;
+        }
+
+        let mut dir_newest: Option<u64> = None;
+        let mut dir_files = 0;
+        for &child in &node.children {
+            let Some(child_node) = index.get(child) else { continue };
+            let (child_newest, child_files) = if child_node.is_dir {
+                newest.get(&child).copied().unwrap_or((None, 0))
+            } else {
+                (child_node.modified_at, 1)
+            };
+            dir_newest = dir_newest.max(child_newest);
+            dir_files += child_files;
+        }
+        newest.insert(id, (dir_newest, dir_files));
+    }
+
+    let threshold = now.saturating_sub(stale_after_secs);
+    let is_stale = |id: NodeId| {
+        newest
+            .get(&id)
+            .is_some_and(|(n, files)| *files > 0 && n.is_some_and(|n| n < threshold))
+    };
+
+    let mut stale: Vec<(u64, NodeId)> = order
+        .iter()
+        .copied()
+        .filter(|&id| is_stale(id))
+        .filter(|&id| {
+            id == start || !index.get(id).and_then(|n| n.parent).is_some_and(is_stale)
+        })
+        .filter_map(|id| Some((index.get(id)?.size, id)))
+        .collect();
+    stale.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    stale.truncate(limit);
+
+    let stale_dirs = stale
+        .into_iter()
+        .filter_map(|(size, id)| {
+            let (newest_modified_at, file_count) = newest.get(&id).copied()?;
+            Some(StaleDir {
+                path: index.path_of(id).to_string_lossy().to_string(),
+                size,
+                file_count,
+                newest_modified_at: newest_modified_at?,
+            })
+        })
+        .collect();
+
+    AgeAnalysis {
+        buckets,
+        unknown_bytes,
+        unknown_count,
+        stale_dirs,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerUsage {
+    /// None for entries whose metadata could not be read
+    pub uid: Option<u32>,
+    pub username: Option<String>,
+    pub bytes: u64,
+    pub file_count: u64,
+    pub dir_count: u64,
+}
+
+/// Bytes and entry counts per owning user under `start`, largest first
+pub fn owner_usage(index: &ScanIndex, start: NodeId) -> Vec<OwnerUsage> {
+    let mut totals: HashMap<Option<u32>, (u64, u64, u64)> = HashMap::new();
+    for id in index.descendants(start) {
+        let Some(node) = index.get(id) else { continue };
+        let entry = totals.entry(node.uid).or_default();
+        if node.is_dir {
+            entry.2 += 1;
+        } else {
+            entry.0 += node.size;
+            entry.1 += 1;
+        }
+    }
+
+    let mut usage: Vec<OwnerUsage> = totals
+        .into_iter()
+        .map(|(uid, (bytes, file_count, dir_count))| OwnerUsage {
+            uid,
+            username: uid.and_then(platform::username_for_uid),
+            bytes,
+            file_count,
+            dir_count,
+        })
+        .collect();
+    usage.sort_unstable_by(|a, b| b.bytes.cmp(&a.bytes).then(a.uid.cmp(&b.uid)));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::{IndexNode, ROOT_ID};
+    use std::path::Path;
+
+    fn file(name: &str, size: u64) -> IndexNode {
+        IndexNode {
+            name: name.to_string(),
+            size,
+            ..Default::default()
+        }
+    }
+
+    fn dir(name: &str) -> IndexNode {
+        IndexNode {
+            name: name.to_string(),
+            is_dir: true,
+            ..Default::default()
+        }
+    }
+
+    fn paths(entries: &[TopEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    /// /r/{big/{f, sub/x}, empty, even/{d1/a, d2/b}, t.bin}
+    fn sizes_fixture() -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 16);
+        let root = index.insert(None, dir("r"));
+        let big = index.insert(Some(root), dir("big"));
+        index.insert(Some(big), file("f", 100));
+        let sub = index.insert(Some(big), dir("sub"));
+        index.insert(Some(sub), file("x", 900));
+        index.insert(Some(root), dir("empty"));
+        let even = index.insert(Some(root), dir("even"));
+        let d1 = index.insert(Some(even), dir("d1"));
+        index.insert(Some(d1), file("a", 300));
+        let d2 = index.insert(Some(even), dir("d2"));
+        index.insert(Some(d2), file("b", 300));
+        index.insert(Some(root), file("t.bin", 50));
+        index.recompute_totals();
+        index
+    }
+
+    #[test]
+    fn top_dirs_skip_pass_through_parents() {
+        let top = top_items(&sizes_fixture(), ROOT_ID, 10);
+        // /r and /r/big each have one subdirectory holding most of their
+        // bytes; /r/even is split exactly in half, which still counts
+        assert_eq!(
+            paths(&top.dirs),
+            vec!["/r/big/sub", "/r/even", "/r/even/d1", "/r/even/d2"]
+        );
+        assert!(top.dirs.iter().all(|d| d.is_dir && d.size > 0));
+    }
+
+    #[test]
+    fn top_items_respect_the_limit_and_keep_the_first_tie() {
+        let index = sizes_fixture();
+        let top = top_items(&index, ROOT_ID, 2);
+        assert_eq!(paths(&top.files), vec!["/r/big/sub/x", "/r/even/d1/a"]);
+        assert_eq!(paths(&top.dirs), vec!["/r/big/sub", "/r/even"]);
+
+        let top = top_items(&index, ROOT_ID, 3);
+        assert_eq!(paths(&top.dirs), vec!["/r/big/sub", "/r/even", "/r/even/d1"]);
+        assert_eq!(top.files.iter().map(|f| f.size).collect::<Vec<_>>(), vec![900, 300, 300]);
+
+        let top = top_items(&index, ROOT_ID, 0);
+        assert!(top.files.is_empty() && top.dirs.is_empty());
+    }
+
+    #[test]
+    fn top_items_stay_under_the_start() {
+        let index = sizes_fixture();
+        let even = index.find(Path::new("/r/even")).unwrap();
+        let top = top_items(&index, even, 10);
+        assert_eq!(paths(&top.files), vec!["/r/even/d1/a", "/r/even/d2/b"]);
+        assert_eq!(paths(&top.dirs), vec!["/r/even", "/r/even/d1", "/r/even/d2"]);
+    }
+
+    const NOW: u64 = 2000 * DAY_SECS;
+
+    fn aged(name: &str, size: u64, age_days: u64) -> IndexNode {
+        IndexNode {
+            modified_at: Some(NOW - age_days * DAY_SECS),
+            ..file(name, size)
+        }
+    }
+
+    /// /r/{old/{a/f, b/g}, mixed/{new, stale/h}, zdir/z, unk/u}
+    fn ages_fixture() -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 16);
+        let root = index.insert(None, dir("r"));
+        let old = index.insert(Some(root), dir("old"));
+        let a = index.insert(Some(old), dir("a"));
+        index.insert(Some(a), aged("f", 10, 400));
+        let b = index.insert(Some(old), dir("b"));
+        index.insert(Some(b), aged("g", 20, 500));
+        let mixed = index.insert(Some(root), dir("mixed"));
+        index.insert(Some(mixed), aged("new", 5, 1));
+        let stale = index.insert(Some(mixed), dir("stale"));
+        index.insert(Some(stale), aged("h", 7, 800));
+        let zdir = index.insert(Some(root), dir("zdir"));
+        index.insert(
+            Some(zdir),
+            IndexNode {
+                modified_at: Some(0),
+                ..file("z", 3)
+            },
+        );
+        let unk = index.insert(Some(root), dir("unk"));
+        index.insert(Some(unk), file("u", 4));
+        index.recompute_totals();
+        index
+    }
+
+    fn bucket<'a>(ages: &'a AgeAnalysis, label: &str) -> &'a AgeBucket {
+        ages.buckets.iter().find(|b| b.label == label).unwrap()
+    }
+
+    #[test]
+    fn ages_fill_buckets_by_mtime() {
+        let ages = age_analysis(&ages_fixture(), ROOT_ID, NOW, 365 * DAY_SECS, 10);
+        let labels: Vec<_> = ages.buckets.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, vec!["1w", "1m", "6m", "1y", "3y", "older"]);
+        assert_eq!(bucket(&ages, "older").max_age_secs, None);
+
+        let filled: Vec<_> = ages.buckets.iter().map(|b| (b.bytes, b.count)).collect();
+        // a zero mtime is as old as it gets
+        assert_eq!(filled, vec![(5, 1), (0, 0), (0, 0), (0, 0), (37, 3), (3, 1)]);
+        assert_eq!((ages.unknown_bytes, ages.unknown_count), (4, 1));
+    }
+
+    #[test]
+    fn bucket_bounds_are_exclusive() {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 4);
+        let root = index.insert(None, dir("r"));
+        index.insert(Some(root), aged("week", 1, 7));
+        index.insert(Some(root), aged("day", 2, 6));
+        index.recompute_totals();
+
+        let ages = age_analysis(&index, ROOT_ID, NOW, 365 * DAY_SECS, 10);
+        assert_eq!(bucket(&ages, "1w").bytes, 2);
+        assert_eq!(bucket(&ages, "1m").bytes, 1);
+    }
+
+    #[test]
+    fn only_the_outermost_stale_dir_is_reported() {
+        let ages = age_analysis(&ages_fixture(), ROOT_ID, NOW, 365 * DAY_SECS, 10);
+        let stale: Vec<_> = ages
+            .stale_dirs
+            .iter()
+            .map(|d| (d.path.as_str(), d.size, d.file_count))
+            .collect();
+        // /r/old/{a,b} are folded into /r/old; /r/mixed has a fresh file so
+        // its stale child stands alone; /r/unk has no known mtime
+        assert_eq!(
+            stale,
+            vec![("/r/old", 30, 2), ("/r/mixed/stale", 7, 1), ("/r/zdir", 3, 1)]
+        );
+        assert_eq!(ages.stale_dirs[0].newest_modified_at, NOW - 400 * DAY_SECS);
+        assert_eq!(ages.stale_dirs[2].newest_modified_at, 0);
+
+        let ages = age_analysis(&ages_fixture(), ROOT_ID, NOW, 365 * DAY_SECS, 1);
+        assert_eq!(ages.stale_dirs.len(), 1);
+    }
+
+    #[test]
+    fn a_stale_start_is_reported_itself() {
+        let index = ages_fixture();
+        let old = index.find(Path::new("/r/old")).unwrap();
+        let ages = age_analysis(&index, old, NOW, 365 * DAY_SECS, 10);
+        let stale: Vec<_> = ages.stale_dirs.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(stale, vec!["/r/old"]);
+
+        // raising the threshold past the newest file clears the chain
+        let ages = age_analysis(&index, old, NOW, 450 * DAY_SECS, 10);
+        let stale: Vec<_> = ages.stale_dirs.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(stale, vec!["/r/old/b"]);
+    }
+
+    /// Far above any real account, so no name resolves for it
+    const NO_NAME_UID: u32 = 3_999_999_999;
+
+    fn owned(node: IndexNode, uid: u32) -> IndexNode {
+        IndexNode {
+            uid: Some(uid),
+            ..node
+        }
+    }
+
+    #[test]
+    fn owners_are_summed_per_uid() {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 8);
+        let root = index.insert(None, owned(dir("r"), 0));
+        let a = index.insert(Some(root), owned(dir("a"), 0));
+        index.insert(Some(a), owned(file("f1", 100), 0));
+        index.insert(Some(a), owned(file("f2", 300), NO_NAME_UID));
+        let b = index.insert(Some(root), owned(dir("b"), NO_NAME_UID));
+        index.insert(Some(b), owned(file("f3", 50), NO_NAME_UID));
+        index.insert(Some(b), file("f4", 7));
+        index.recompute_totals();
+
+        let usage = owner_usage(&index, ROOT_ID);
+        let sums: Vec<_> = usage
+            .iter()
+            .map(|u| (u.uid, u.bytes, u.file_count, u.dir_count))
+            .collect();
+        assert_eq!(
+            sums,
+            vec![
+                (Some(NO_NAME_UID), 350, 2, 1),
+                (Some(0), 100, 1, 2),
+                (None, 7, 1, 0),
+            ]
+        );
+        assert_eq!(usage[0].username, None);
+        assert_eq!(usage[1].username, platform::username_for_uid(0));
+        assert_eq!(usage[2].username, None);
+
+        let usage = owner_usage(&index, b);
+        let sums: Vec<_> = usage.iter().map(|u| (u.uid, u.bytes)).collect();
+        assert_eq!(sums, vec![(Some(NO_NAME_UID), 50), (None, 7)]);
+    }
+}
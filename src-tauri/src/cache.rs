@@ -3,9 +3,15 @@
 //! Stores scan snapshots in a local SQLite database to enable fast reloads
 //! and incremental updates without re-walking the filesystem.
 
+use crate::analysis::ExtensionStat;
+use crate::category::FileCategory;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::index::ScanIndex;
 use crate::scanner::FileNode;
+use crate::workspace::{self, Workspace};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -30,9 +36,11 @@ pub struct CachedScan {
     pub total_size: u64,
     /// The scan result tree
     pub root: FileNode,
+    /// Per-extension totals for the whole scan (not just the capped tree)
+    pub extension_stats: Vec<ExtensionStat>,
 }
 
-const CACHE_VERSION: u32 = 1;
+const CACHE_VERSION: u32 = 5;
 
 /// Get the cache directory path
 fn get_cache_dir() -> Option<PathBuf> {
@@ -44,15 +52,16 @@ fn get_db_path() -> Option<PathBuf> {
     get_cache_dir().map(|p| p.join("spaceview.db"))
 }
 
-fn open_db() -> Result<Connection, String> {
-    let db_path = get_db_path().ok_or("Could not determine cache directory")?;
+fn open_db() -> AppResult<Connection> {
+    let db_path = get_db_path()
+        .ok_or_else(|| AppError::internal("Could not determine cache directory"))?;
     if let Some(parent) = db_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+            .map_err(|e| AppError::io("Failed to create cache directory", &e).with_path(parent))?;
     }
 
     let conn = Connection::open(db_path)
-        .map_err(|e| format!("Failed to open cache DB: {}", e))?;
+        .map_err(|e| AppError::database("Failed to open cache DB", e))?;
 
     conn.execute_batch(
         r#"
@@ -76,21 +85,71 @@ fn open_db() -> Result<Connection, String> {
           size_bytes INTEGER NOT NULL,
           deleted_at INTEGER NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS scan_indexes (
+          scan_path TEXT PRIMARY KEY,
+          version INTEGER NOT NULL,
+          node_count INTEGER NOT NULL,
+          index_blob BLOB NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS category_overrides (
+          extension TEXT PRIMARY KEY,
+          category TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS settings (
+          key TEXT PRIMARY KEY,
+          value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS workspaces (
+          name TEXT PRIMARY KEY,
+          roots TEXT NOT NULL,
+          updated_at INTEGER NOT NULL
+        );
         "#,
     )
-    .map_err(|e| format!("Failed to init cache DB: {}", e))?;
+    .map_err(|e| AppError::database("Failed to init cache DB", e))?;
+    add_column(&conn, "delete_log", "batch_id", "TEXT")?;
+    add_column(&conn, "delete_log", "restored_at", "INTEGER")?;
+    add_column(&conn, "delete_log", "is_dir", "INTEGER")?;
+    add_column(&conn, "delete_log", "extension", "TEXT")?;
+    add_column(&conn, "delete_log", "category", "TEXT")?;
+    add_column(&conn, "delete_log", "file_count", "INTEGER")?;
+    add_column(&conn, "delete_log", "dir_count", "INTEGER")?;
+    add_column(&conn, "delete_log", "mode", "TEXT")?;
 
     Ok(conn)
 }
 
+/// Add a column to a table created by an older version, if it's missing
+fn add_column(conn: &Connection, table: &str, column: &str, decl: &str) -> AppResult<()> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ))
+        .and_then(|mut stmt| stmt.exists(params![column]))
+        .map_err(|e| AppError::database("Failed to read cache DB schema", e))?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+            [],
+        )
+        .map_err(|e| AppError::database("Failed to upgrade cache DB", e))?;
+    }
+    Ok(())
+}
+
 /// Maximum cache size (500MB) to prevent memory issues
 const MAX_CACHE_SIZE: u64 = 500 * 1024 * 1024;
 
 /// Save scan results to cache (full scan)
-pub fn save_to_cache(scan_path: &str, root: &FileNode) -> Result<PathBuf, String> {
+pub fn save_to_cache(
+    scan_path: &str,
+    root: &FileNode,
+    extension_stats: &[ExtensionStat],
+) -> AppResult<PathBuf> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .map_err(|e| format!("Time error: {}", e))?
+        .map_err(|e| AppError::internal(format!("Time error: {}", e)))?
         .as_secs();
 
     // Count totals from the tree
@@ -105,18 +164,22 @@ pub fn save_to_cache(scan_path: &str, root: &FileNode) -> Result<PathBuf, String
         total_dirs,
         total_size: root.size,
         root: root.clone(),
+        extension_stats: extension_stats.to_vec(),
     };
 
     // First serialize to bytes to check size
     let serialized = bincode::serialize(&cached)
-        .map_err(|e| format!("Failed to serialize cache: {}", e))?;
+        .map_err(|e| AppError::internal(format!("Failed to serialize cache: {}", e)))?;
 
     // Check if cache is too large
     if serialized.len() as u64 > MAX_CACHE_SIZE {
-        return Err(format!(
-            "Cache too large ({:.1} MB > {:.0} MB limit), skipping",
-            serialized.len() as f64 / 1_048_576.0,
-            MAX_CACHE_SIZE as f64 / 1_048_576.0
+        return Err(AppError::new(
+            ErrorCode::CacheTooLarge,
+            format!(
+                "Cache too large ({:.1} MB > {:.0} MB limit), skipping",
+                serialized.len() as f64 / 1_048_576.0,
+                MAX_CACHE_SIZE as f64 / 1_048_576.0
+            ),
         ));
     }
 
@@ -149,16 +212,20 @@ pub fn save_to_cache(scan_path: &str, root: &FileNode) -> Result<PathBuf, String
             serialized
         ],
     )
-    .map_err(|e| format!("Failed to write cache DB: {}", e))?;
+    .map_err(|e| AppError::database("Failed to write cache DB", e))?;
 
     Ok(get_db_path().unwrap_or_default())
 }
 
 /// Save incremental scan update (keeps original scanned_at)
-pub fn save_incremental_update(scan_path: &str, root: &FileNode) -> Result<(), String> {
+pub fn save_incremental_update(
+    scan_path: &str,
+    root: &FileNode,
+    extension_stats: &[ExtensionStat],
+) -> AppResult<()> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .map_err(|e| format!("Time error: {}", e))?
+        .map_err(|e| AppError::internal(format!("Time error: {}", e)))?
         .as_secs();
 
     let conn = open_db()?;
@@ -169,7 +236,7 @@ pub fn save_incremental_update(scan_path: &str, root: &FileNode) -> Result<(), S
             |row| row.get(0),
         )
         .optional()
-        .map_err(|e| format!("Failed to read cache metadata: {}", e))?;
+        .map_err(|e| AppError::database("Failed to read cache metadata", e))?;
 
     let scanned_at = scanned_at.unwrap_or(now as i64) as u64;
 
@@ -183,16 +250,20 @@ pub fn save_incremental_update(scan_path: &str, root: &FileNode) -> Result<(), S
         total_dirs,
         total_size: root.size,
         root: root.clone(),
+        extension_stats: extension_stats.to_vec(),
     };
 
     let serialized = bincode::serialize(&cached)
-        .map_err(|e| format!("Failed to serialize cache: {}", e))?;
+        .map_err(|e| AppError::internal(format!("Failed to serialize cache: {}", e)))?;
 
     if serialized.len() as u64 > MAX_CACHE_SIZE {
-        return Err(format!(
-            "Cache too large ({:.1} MB > {:.0} MB limit), skipping",
-            serialized.len() as f64 / 1_048_576.0,
-            MAX_CACHE_SIZE as f64 / 1_048_576.0
+        return Err(AppError::new(
+            ErrorCode::CacheTooLarge,
+            format!(
+                "Cache too large ({:.1} MB > {:.0} MB limit), skipping",
+                serialized.len() as f64 / 1_048_576.0,
+                MAX_CACHE_SIZE as f64 / 1_048_576.0
+            ),
         ));
     }
 
@@ -224,13 +295,13 @@ pub fn save_incremental_update(scan_path: &str, root: &FileNode) -> Result<(), S
             serialized
         ],
     )
-    .map_err(|e| format!("Failed to write cache DB: {}", e))?;
+    .map_err(|e| AppError::database("Failed to write cache DB", e))?;
 
     Ok(())
 }
 
 /// Load scan results from cache
-pub fn load_from_cache(scan_path: &str) -> Result<CachedScan, String> {
+pub fn load_from_cache(scan_path: &str) -> AppResult<CachedScan> {
     let conn = open_db()?;
     let row = conn
         .query_row(
@@ -254,19 +325,26 @@ pub fn load_from_cache(scan_path: &str) -> Result<CachedScan, String> {
             },
         )
         .optional()
-        .map_err(|e| format!("Failed to read cache DB: {}", e))?;
+        .map_err(|e| AppError::database("Failed to read cache DB", e))?;
 
     let Some((version, scanned_at, last_incremental_at, total_files, total_dirs, total_size, blob)) = row
     else {
-        return Err("Cache not found".to_string());
+        return Err(AppError::new(ErrorCode::CacheMiss, "Cache not found").with_path(scan_path));
     };
 
     if version as u32 != CACHE_VERSION {
-        return Err(format!("Cache version mismatch: {} vs {}", version, CACHE_VERSION));
+        return Err(AppError::new(
+            ErrorCode::CacheVersionMismatch,
+            format!("Cache version mismatch: {} vs {}", version, CACHE_VERSION),
+        )
+        .with_path(scan_path));
     }
 
     let cached: CachedScan = bincode::deserialize(&blob)
-        .map_err(|e| format!("Failed to deserialize cache: {}", e))?;
+        .map_err(|e| {
+            AppError::new(ErrorCode::CacheCorrupt, format!("Failed to deserialize cache: {}", e))
+                .with_path(scan_path)
+        })?;
 
     Ok(CachedScan {
         version: cached.version,
@@ -277,9 +355,235 @@ pub fn load_from_cache(scan_path: &str) -> Result<CachedScan, String> {
         total_dirs: total_dirs as u64,
         total_size: total_size as u64,
         root: cached.root,
+        extension_stats: cached.extension_stats,
+    })
+}
+
+/// Save the full-resolution scan index alongside the cached tree
+pub fn save_index(scan_path: &str, index: &ScanIndex) -> AppResult<()> {
+    let serialized = bincode::serialize(index)
+        .map_err(|e| AppError::internal(format!("Failed to serialize index: {}", e)))?;
+
+    if serialized.len() as u64 > MAX_CACHE_SIZE {
+        return Err(AppError::new(
+            ErrorCode::CacheTooLarge,
+            format!(
+                "Index too large ({:.1} MB > {:.0} MB limit), skipping",
+                serialized.len() as f64 / 1_048_576.0,
+                MAX_CACHE_SIZE as f64 / 1_048_576.0
+            ),
+        ));
+    }
+
+    let conn = open_db()?;
+    conn.execute(
+        r#"
+        INSERT INTO scan_indexes (scan_path, version, node_count, index_blob)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT(scan_path) DO UPDATE SET
+          version = excluded.version,
+          node_count = excluded.node_count,
+          index_blob = excluded.index_blob
+        "#,
+        params![scan_path, CACHE_VERSION as i64, index.len() as i64, serialized],
+    )
+    .map_err(|e| AppError::database("Failed to write index", e))?;
+
+    Ok(())
+}
+
+/// Load the full-resolution scan index for a path
+pub fn load_index(scan_path: &str) -> AppResult<ScanIndex> {
+    let conn = open_db()?;
+    let row = conn
+        .query_row(
+            "SELECT version, index_blob FROM scan_indexes WHERE scan_path = ?1",
+            params![scan_path],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?)),
+        )
+        .optional()
+        .map_err(|e| AppError::database("Failed to read index", e))?;
+
+    let Some((version, blob)) = row else {
+        return Err(AppError::new(ErrorCode::CacheMiss, "Index not found").with_path(scan_path));
+    };
+
+    if version as u32 != CACHE_VERSION {
+        return Err(AppError::new(
+            ErrorCode::CacheVersionMismatch,
+            format!("Index version mismatch: {} vs {}", version, CACHE_VERSION),
+        )
+        .with_path(scan_path));
+    }
+
+    bincode::deserialize(&blob).map_err(|e| {
+            AppError::new(ErrorCode::CacheCorrupt, format!("Failed to deserialize index: {}", e))
+                .with_path(scan_path)
+        })
+}
+
+/// Load user extension -> category overrides
+pub fn load_category_overrides() -> HashMap<String, FileCategory> {
+    let conn = match open_db() {
+        Ok(c) => c,
+        Err(_) => return HashMap::new(),
+    };
+
+    let mut stmt = match conn.prepare("SELECT extension, category FROM category_overrides") {
+        Ok(s) => s,
+        Err(_) => return HashMap::new(),
+    };
+
+    let rows = match stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    }) {
+        Ok(r) => r,
+        Err(_) => return HashMap::new(),
+    };
+
+    rows.filter_map(Result::ok)
+        .filter_map(|(ext, category)| Some((ext, FileCategory::parse(&category)?)))
+        .collect()
+}
+
+/// Set (or clear, with None) the category override for an extension
+pub fn set_category_override(extension: &str, category: Option<FileCategory>) -> AppResult<()> {
+    let conn = open_db()?;
+    match category {
+        Some(category) => conn.execute(
+            r#"
+            INSERT INTO category_overrides (extension, category) VALUES (?1, ?2)
+            ON CONFLICT(extension) DO UPDATE SET category = excluded.category
+            "#,
+            params![extension, category.as_str()],
+        ),
+        None => conn.execute(
+            "DELETE FROM category_overrides WHERE extension = ?1",
+            params![extension],
+        ),
+    }
+    .map_err(|e| AppError::database("Failed to write category override", e))?;
+    Ok(())
+}
+
+/// Read a stored setting
+pub fn load_setting(key: &str) -> Option<String> {
+    let conn = open_db().ok()?;
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+    .ok()
+    .flatten()
+}
+
+/// Store a setting, replacing any previous value
+pub fn save_setting(key: &str, value: &str) -> AppResult<()> {
+    let conn = open_db()?;
+    conn.execute(
+        r#"
+        INSERT INTO settings (key, value) VALUES (?1, ?2)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value
+        "#,
+        params![key, value],
+    )
+    .map_err(|e| AppError::database("Failed to write setting", e))?;
+    Ok(())
+}
+
+/// Create or replace a workspace definition (roots stored as a JSON array)
+pub fn save_workspace(workspace: &Workspace) -> AppResult<()> {
+    let roots = serde_json::to_string(&workspace.roots)
+        .map_err(|e| AppError::internal(format!("Failed to encode workspace roots: {}", e)))?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| AppError::internal(format!("Time error: {}", e)))?
+        .as_secs();
+    let conn = open_db()?;
+    conn.execute(
+        r#"
+        INSERT INTO workspaces (name, roots, updated_at) VALUES (?1, ?2, ?3)
+        ON CONFLICT(name) DO UPDATE SET roots = excluded.roots, updated_at = excluded.updated_at
+        "#,
+        params![workspace.name, roots, now as i64],
+    )
+    .map_err(|e| AppError::database("Failed to write workspace", e))?;
+    Ok(())
+}
+
+pub fn load_workspace(name: &str) -> Option<Workspace> {
+    let conn = open_db().ok()?;
+    let roots: String = conn
+        .query_row(
+            "SELECT roots FROM workspaces WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()??;
+    Some(Workspace {
+        name: name.to_string(),
+        roots: serde_json::from_str(&roots).ok()?,
     })
 }
 
+/// All workspace definitions, most recently updated first
+pub fn list_workspaces() -> Vec<Workspace> {
+    let conn = match open_db() {
+        Ok(c) => c,
+        Err(_) => return vec![],
+    };
+
+    let mut stmt = match conn.prepare("SELECT name, roots FROM workspaces ORDER BY updated_at DESC") {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    let rows = match stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    }) {
+        Ok(r) => r,
+        Err(_) => return vec![],
+    };
+
+    rows.filter_map(Result::ok)
+        .filter_map(|(name, roots)| {
+            Some(Workspace {
+                name,
+                roots: serde_json::from_str(&roots).ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Remove a workspace definition and its cached scan
+pub fn delete_workspace(name: &str) -> AppResult<()> {
+    let conn = open_db()?;
+    conn.execute("DELETE FROM workspaces WHERE name = ?1", params![name])
+        .map_err(|e| AppError::database("Failed to delete workspace", e))?;
+    delete_cache(&workspace::workspace_key(name))
+}
+
+/// Entry count (files plus directories) of the last scan of a path.
+///
+/// Read from the saved index, which covers every entry; the totals in
+/// `scans` come from the capped UI tree and undercount large scans, so they
+/// are only a fallback when the index was too large to save.
+pub fn load_entry_count(scan_path: &str) -> Option<u64> {
+    let conn = open_db().ok()?;
+    let count = |sql: &str| {
+        conn.query_row(sql, params![scan_path], |row| row.get::<_, i64>(0))
+            .optional()
+            .ok()
+            .flatten()
+    };
+    count("SELECT node_count FROM scan_indexes WHERE scan_path = ?1")
+        .or_else(|| count("SELECT total_files + total_dirs FROM scans WHERE scan_path = ?1"))
+        .map(|count| count as u64)
+}
+
 /// Check if cache exists for a path
 #[allow(dead_code)]
 pub fn has_cache(scan_path: &str) -> bool {
@@ -319,21 +623,24 @@ pub fn get_cache_info(scan_path: &str) -> Option<CacheInfo> {
 }
 
 /// Delete cache for a path
-pub fn delete_cache(scan_path: &str) -> Result<(), String> {
+pub fn delete_cache(scan_path: &str) -> AppResult<()> {
     let conn = open_db()?;
     conn.execute("DELETE FROM scans WHERE scan_path = ?1", params![scan_path])
-        .map_err(|e| format!("Failed to delete cache: {}", e))?;
+        .map_err(|e| AppError::database("Failed to delete cache", e))?;
+    conn.execute("DELETE FROM scan_indexes WHERE scan_path = ?1", params![scan_path])
+        .map_err(|e| AppError::database("Failed to delete index", e))?;
     Ok(())
 }
 
 /// Clear all caches
-pub fn clear_all_caches() -> Result<usize, String> {
+pub fn clear_all_caches() -> AppResult<usize> {
     let conn = open_db()?;
     let count: i64 = conn
         .query_row("SELECT COUNT(*) FROM scans", [], |row| row.get(0))
         .unwrap_or(0);
     conn.execute("DELETE FROM scans", [])
-        .map_err(|e| format!("Failed to clear cache: {}", e))?;
+        .map_err(|e| AppError::database("Failed to clear cache", e))?;
+    let _ = conn.execute("DELETE FROM scan_indexes", []);
     let _ = conn.execute("DELETE FROM delete_log", []);
     Ok(count as usize)
 }
@@ -410,24 +717,115 @@ pub fn get_scan_history() -> Vec<ScanHistoryEntry> {
     rows.filter_map(Result::ok).collect()
 }
 
+/// How a logged item was removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteMode {
+    /// Moved to the trash (entries logged before modes existed included)
+    #[default]
+    Trash,
+    /// Unlinked without going through the trash
+    Permanent,
+    /// Overwritten with zeros, then unlinked
+    Overwrite,
+}
+
+impl DeleteMode {
+    pub const ALL: [DeleteMode; 3] = [
+        DeleteMode::Trash,
+        DeleteMode::Permanent,
+        DeleteMode::Overwrite,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeleteMode::Trash => "trash",
+            DeleteMode::Permanent => "permanent",
+            DeleteMode::Overwrite => "overwrite",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == s)
+    }
+}
+
+/// What one logged delete removed, as indexed at deletion time
+#[derive(Debug, Clone)]
+pub struct DeleteRecord {
+    pub target_path: String,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+    /// Extension of a file; None for directories
+    pub extension: Option<String>,
+    /// Category of a file, or the one holding the most bytes in a directory
+    pub category: Option<FileCategory>,
+    /// Files removed (1 for a file)
+    pub file_count: u64,
+    /// Directories removed, including the target itself
+    pub dir_count: u64,
+    pub mode: DeleteMode,
+}
+
 /// Log delete operation for trust UI
-pub fn log_delete(scan_path: &str, target_path: &str, size_bytes: u64) -> Result<(), String> {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| format!("Time error: {}", e))?
-        .as_secs();
+pub fn log_delete(scan_path: &str, record: &DeleteRecord) -> AppResult<()> {
+    insert_delete_records(scan_path, None, std::slice::from_ref(record))
+}
 
-    let conn = open_db()?;
-    conn.execute(
-        r#"
-        INSERT INTO delete_log (scan_path, target_path, size_bytes, deleted_at)
-        VALUES (?1, ?2, ?3, ?4)
-        "#,
-        params![scan_path, target_path, size_bytes as i64, now as i64],
-    )
-    .map_err(|e| format!("Failed to write delete log: {}", e))?;
+/// Log the items deleted by one batch, all under `batch_id`
+pub fn log_delete_batch(
+    scan_path: &str,
+    batch_id: &str,
+    records: &[DeleteRecord],
+) -> AppResult<()> {
+    insert_delete_records(scan_path, Some(batch_id), records)
+}
 
-    Ok(())
+fn insert_delete_records(
+    scan_path: &str,
+    batch_id: Option<&str>,
+    records: &[DeleteRecord],
+) -> AppResult<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let mut conn = open_db()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::database("Failed to write delete log", e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                r#"
+                INSERT INTO delete_log (
+                  scan_path, target_path, size_bytes, deleted_at, batch_id,
+                  is_dir, extension, category, file_count, dir_count, mode
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                "#,
+            )
+            .map_err(|e| AppError::database("Failed to write delete log", e))?;
+        for record in records {
+            stmt.execute(params![
+                scan_path,
+                record.target_path,
+                record.size_bytes as i64,
+                now as i64,
+                batch_id,
+                record.is_dir,
+                record.extension,
+                record.category.map(|c| c.as_str()),
+                record.file_count as i64,
+                record.dir_count as i64,
+                record.mode.as_str(),
+            ])
+            .map_err(|e| AppError::database("Failed to write delete log", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| AppError::database("Failed to write delete log", e))
 }
 
 /// Read recent delete log entries
@@ -439,7 +837,8 @@ pub fn get_delete_log(scan_path: &str, limit: usize) -> Vec<DeleteLogEntry> {
 
     let mut stmt = match conn.prepare(
         r#"
-        SELECT id, scan_path, target_path, size_bytes, deleted_at
+        SELECT id, scan_path, target_path, size_bytes, deleted_at, batch_id, restored_at,
+          extension, category, file_count, mode
         FROM delete_log
         WHERE scan_path = ?1
         ORDER BY deleted_at DESC
@@ -450,15 +849,7 @@ pub fn get_delete_log(scan_path: &str, limit: usize) -> Vec<DeleteLogEntry> {
         Err(_) => return vec![],
     };
 
-    let rows = match stmt.query_map(params![scan_path, limit as i64], |row| {
-        Ok(DeleteLogEntry {
-            id: row.get::<_, i64>(0)? as u64,
-            scan_path: row.get::<_, String>(1)?,
-            target_path: row.get::<_, String>(2)?,
-            size_bytes: row.get::<_, i64>(3)? as u64,
-            deleted_at: row.get::<_, i64>(4)? as u64,
-        })
-    }) {
+    let rows = match stmt.query_map(params![scan_path, limit as i64], delete_log_row) {
         Ok(r) => r,
         Err(_) => return vec![],
     };
@@ -466,6 +857,194 @@ pub fn get_delete_log(scan_path: &str, limit: usize) -> Vec<DeleteLogEntry> {
     rows.filter_map(Result::ok).collect()
 }
 
+/// One delete log entry by id
+pub fn get_delete_entry(id: u64) -> AppResult<Option<DeleteLogEntry>> {
+    let conn = open_db()?;
+    conn.query_row(
+        r#"
+        SELECT id, scan_path, target_path, size_bytes, deleted_at, batch_id, restored_at,
+          extension, category, file_count, mode
+        FROM delete_log
+        WHERE id = ?1
+        "#,
+        params![id as i64],
+        delete_log_row,
+    )
+    .optional()
+    .map_err(|e| AppError::database("Failed to read delete log", e))
+}
+
+/// Record that a logged delete was undone
+pub fn mark_restored(id: u64) -> AppResult<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let conn = open_db()?;
+    conn.execute(
+        "UPDATE delete_log SET restored_at = ?1 WHERE id = ?2",
+        params![now as i64, id as i64],
+    )
+    .map_err(|e| AppError::database("Failed to update delete log", e))?;
+    Ok(())
+}
+
+fn delete_log_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DeleteLogEntry> {
+    Ok(DeleteLogEntry {
+        id: row.get::<_, i64>(0)? as u64,
+        scan_path: row.get::<_, String>(1)?,
+        target_path: row.get::<_, String>(2)?,
+        size_bytes: row.get::<_, i64>(3)? as u64,
+        deleted_at: row.get::<_, i64>(4)? as u64,
+        batch_id: row.get::<_, Option<String>>(5)?,
+        restored_at: row.get::<_, Option<i64>>(6)?.map(|t| t as u64),
+        extension: row.get::<_, Option<String>>(7)?,
+        category: row
+            .get::<_, Option<String>>(8)?
+            .and_then(|c| FileCategory::parse(&c)),
+        file_count: row.get::<_, Option<i64>>(9)?.map(|n| n as u64),
+        mode: row
+            .get::<_, Option<String>>(10)?
+            .and_then(|m| DeleteMode::parse(&m))
+            .unwrap_or_default(),
+    })
+}
+
+/// Bucket for `delete_stats` over time
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    #[default]
+    Day,
+    /// Weeks starting on Monday
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DeleteStatsQuery {
+    /// All scan paths when None
+    pub scan_path: Option<String>,
+    /// Unix seconds, inclusive
+    pub since: Option<u64>,
+    /// Unix seconds, exclusive
+    pub until: Option<u64>,
+    pub period: StatsPeriod,
+    /// Most extensions returned (default 20)
+    pub extension_limit: Option<u32>,
+}
+
+/// Space reclaimed by the deletes sharing one key
+#[derive(Debug, Clone, Serialize)]
+pub struct ReclaimedGroup {
+    /// Period start ("2024-05-06", "2024-05"), scan path, category or
+    /// extension; None where the log has no value (e.g. older entries)
+    pub key: Option<String>,
+    pub bytes: u64,
+    /// Delete log entries
+    pub deletes: u64,
+    /// Files removed, where the log recorded it
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteStats {
+    pub total_bytes: u64,
+    pub total_deletes: u64,
+    pub total_files: u64,
+    pub by_period: Vec<ReclaimedGroup>,
+    pub by_scan_path: Vec<ReclaimedGroup>,
+    pub by_category: Vec<ReclaimedGroup>,
+    /// Deleted files only; a directory has no single extension
+    pub by_extension: Vec<ReclaimedGroup>,
+}
+
+/// Space reclaimed by logged deletes, grouped several ways. Restored
+/// entries are left out since their space came back.
+pub fn delete_stats(query: &DeleteStatsQuery) -> AppResult<DeleteStats> {
+    let local_time = "deleted_at, 'unixepoch', 'localtime'";
+    let period = match query.period {
+        StatsPeriod::Day => format!("date({})", local_time),
+        StatsPeriod::Week => format!("date({}, '-6 days', 'weekday 1')", local_time),
+        StatsPeriod::Month => format!("strftime('%Y-%m', {})", local_time),
+    };
+    let extension_limit = query.extension_limit.unwrap_or(20);
+
+    let conn = open_db()?;
+    let totals = reclaimed_groups(&conn, query, "NULL", "")?;
+    let total = totals.into_iter().next();
+    Ok(DeleteStats {
+        total_bytes: total.as_ref().map_or(0, |t| t.bytes),
+        total_deletes: total.as_ref().map_or(0, |t| t.deletes),
+        total_files: total.as_ref().map_or(0, |t| t.files),
+        by_period: reclaimed_groups(&conn, query, &period, "GROUP BY key ORDER BY key")?,
+        by_scan_path: reclaimed_groups(
+            &conn,
+            query,
+            "scan_path",
+            "GROUP BY key ORDER BY bytes DESC",
+        )?,
+        by_category: reclaimed_groups(
+            &conn,
+            query,
+            "category",
+            "GROUP BY key ORDER BY bytes DESC",
+        )?,
+        by_extension: reclaimed_groups(
+            &conn,
+            query,
+            "extension",
+            &format!(
+                "AND is_dir = 0 GROUP BY key ORDER BY bytes DESC LIMIT {}",
+                extension_limit
+            ),
+        )?,
+    })
+}
+
+/// Sum the matching, unrestored deletes by `key` (an SQL expression).
+/// `tail` goes after the filter: extra conditions, grouping and order.
+fn reclaimed_groups(
+    conn: &Connection,
+    query: &DeleteStatsQuery,
+    key: &str,
+    tail: &str,
+) -> AppResult<Vec<ReclaimedGroup>> {
+    let sql = format!(
+        r#"
+        SELECT {} AS key, COALESCE(SUM(size_bytes), 0) AS bytes, COUNT(*),
+          COALESCE(SUM(file_count), 0)
+        FROM delete_log
+        WHERE restored_at IS NULL
+          AND (?1 IS NULL OR scan_path = ?1)
+          AND (?2 IS NULL OR deleted_at >= ?2)
+          AND (?3 IS NULL OR deleted_at < ?3)
+          {}
+        "#,
+        key, tail
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::database("Failed to read delete stats", e))?;
+    let rows = stmt
+        .query_map(
+            params![
+                query.scan_path,
+                query.since.map(|t| t as i64),
+                query.until.map(|t| t as i64)
+            ],
+            |row| {
+                Ok(ReclaimedGroup {
+                    key: row.get::<_, Option<String>>(0)?,
+                    bytes: row.get::<_, i64>(1)? as u64,
+                    deletes: row.get::<_, i64>(2)? as u64,
+                    files: row.get::<_, i64>(3)? as u64,
+                })
+            },
+        )
+        .map_err(|e| AppError::database("Failed to read delete stats", e))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| AppError::database("Failed to read delete stats", e))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteLogEntry {
     pub id: u64,
@@ -473,4 +1052,14 @@ pub struct DeleteLogEntry {
     pub target_path: String,
     pub size_bytes: u64,
     pub deleted_at: u64,
+    /// Set for entries deleted together by `execute_delete`
+    pub batch_id: Option<String>,
+    /// When `restore_deleted` put the item back
+    pub restored_at: Option<u64>,
+    /// File type and files removed, as indexed at deletion time (None for
+    /// entries logged by older versions)
+    pub extension: Option<String>,
+    pub category: Option<FileCategory>,
+    pub file_count: Option<u64>,
+    pub mode: DeleteMode,
 }
@@ -0,0 +1,212 @@
+//! File type categories keyed by extension.
+//!
+//! The built-in sets mirror the frontend (`types.ts`); user overrides are
+//! layered on top so a `CategoryTable` is the single source of truth for
+//! search filters and type statistics.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    Code,
+    Image,
+    Video,
+    Audio,
+    Archive,
+    Document,
+    Other,
+}
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 7] = [
+        FileCategory::Code,
+        FileCategory::Image,
+        FileCategory::Video,
+        FileCategory::Audio,
+        FileCategory::Archive,
+        FileCategory::Document,
+        FileCategory::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileCategory::Code => "code",
+            FileCategory::Image => "image",
+            FileCategory::Video => "video",
+            FileCategory::Audio => "audio",
+            FileCategory::Archive => "archive",
+            FileCategory::Document => "document",
+            FileCategory::Other => "other",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+}
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "swift", "js", "ts", "jsx", "tsx", "py", "rb", "go", "rs", "java",
+    "kt", "c", "cpp", "h", "hpp", "cs", "php", "html", "css", "scss",
+    "json", "xml", "yaml", "yml", "md", "sh", "bash", "zsh",
+];
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "tiff", "webp", "svg", "ico",
+    "heic", "heif", "raw", "psd", "ai",
+];
+
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "mov", "avi", "mkv", "wmv", "flv", "webm", "m4v", "mpeg", "mpg", "3gp",
+];
+
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "wav", "aac", "flac", "ogg", "wma", "m4a", "aiff", "alac",
+];
+
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "tar", "gz", "rar", "7z", "bz2", "xz", "dmg", "iso", "pkg",
+];
+
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "txt", "rtf",
+    "odt", "ods", "odp", "pages", "numbers", "keynote",
+];
+
+const BUILTIN_SETS: [(FileCategory, &[&str]); 6] = [
+    (FileCategory::Code, CODE_EXTENSIONS),
+    (FileCategory::Image, IMAGE_EXTENSIONS),
+    (FileCategory::Video, VIDEO_EXTENSIONS),
+    (FileCategory::Audio, AUDIO_EXTENSIONS),
+    (FileCategory::Archive, ARCHIVE_EXTENSIONS),
+    (FileCategory::Document, DOCUMENT_EXTENSIONS),
+];
+
+/// Extension -> category lookup (built-ins plus user overrides)
+#[derive(Debug, Clone)]
+pub struct CategoryTable {
+    map: HashMap<String, FileCategory>,
+    overrides: HashMap<String, FileCategory>,
+}
+
+impl CategoryTable {
+    pub fn with_overrides(overrides: HashMap<String, FileCategory>) -> Self {
+        let mut map = HashMap::new();
+        // Earlier sets win, matching the frontend's lookup order
+        for (category, extensions) in BUILTIN_SETS.iter().rev() {
+            for ext in extensions.iter() {
+                map.insert(ext.to_string(), *category);
+            }
+        }
+        for (ext, category) in &overrides {
+            map.insert(ext.clone(), *category);
+        }
+        Self { map, overrides }
+    }
+
+    /// Map a (lowercase) file extension to its category
+    pub fn category_of(&self, extension: Option<&str>) -> FileCategory {
+        extension
+            .and_then(|ext| self.map.get(ext).copied())
+            .unwrap_or(FileCategory::Other)
+    }
+
+    pub fn overrides(&self) -> &HashMap<String, FileCategory> {
+        &self.overrides
+    }
+}
+
+impl Default for CategoryTable {
+    fn default() -> Self {
+        Self::with_overrides(HashMap::new())
+    }
+}
+
+/// Normalize user-supplied extensions (".MP4" -> "mp4")
+pub fn normalize_extension(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{self, ExtensionStat};
+
+    fn stat(extension: Option<&str>, bytes: u64, count: u64) -> ExtensionStat {
+        ExtensionStat {
+            extension: extension.map(str::to_string),
+            bytes,
+            count,
+        }
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_builtins() {
+        let overrides = HashMap::from([
+            ("md".to_string(), FileCategory::Document),
+            ("blend".to_string(), FileCategory::Image),
+        ]);
+        let table = CategoryTable::with_overrides(overrides);
+
+        assert_eq!(table.category_of(Some("md")), FileCategory::Document);
+        assert_eq!(table.category_of(Some("blend")), FileCategory::Image);
+        // Built-ins without an override are unchanged
+        assert_eq!(table.category_of(Some("rs")), FileCategory::Code);
+        assert_eq!(table.category_of(Some("mkv")), FileCategory::Video);
+        assert_eq!(table.overrides().len(), 2);
+    }
+
+    #[test]
+    fn unknown_or_missing_extension_is_other() {
+        let table = CategoryTable::default();
+        assert_eq!(table.category_of(Some("nope")), FileCategory::Other);
+        assert_eq!(table.category_of(None), FileCategory::Other);
+        assert!(table.overrides().is_empty());
+    }
+
+    #[test]
+    fn normalize_extension_strips_dot_and_case() {
+        assert_eq!(normalize_extension(".MP4"), "mp4");
+        assert_eq!(normalize_extension("  Tar.GZ "), "tar.gz");
+        assert_eq!(normalize_extension("rs"), "rs");
+        assert_eq!(normalize_extension(""), "");
+        assert_eq!(normalize_extension("."), "");
+    }
+
+    #[test]
+    fn type_stats_groups_by_category() {
+        let table = CategoryTable::with_overrides(HashMap::from([(
+            "txt".to_string(),
+            FileCategory::Code,
+        )]));
+        let extensions = [
+            stat(Some("mp4"), 1000, 2),
+            stat(Some("mkv"), 500, 1),
+            stat(Some("rs"), 30, 3),
+            stat(Some("txt"), 20, 4),
+            stat(None, 5, 1),
+        ];
+
+        let stats = analysis::type_stats(&extensions, &table);
+
+        assert_eq!(stats.total_bytes, 1555);
+        assert_eq!(stats.total_files, 11);
+        let by_category: Vec<_> = stats
+            .by_category
+            .iter()
+            .map(|c| (c.category, c.bytes, c.count))
+            .collect();
+        assert_eq!(
+            by_category,
+            vec![
+                (FileCategory::Video, 1500, 3),
+                (FileCategory::Code, 50, 7),
+                (FileCategory::Other, 5, 1),
+            ]
+        );
+        assert_eq!(stats.by_extension.len(), extensions.len());
+        assert_eq!(stats.by_extension[3].category, FileCategory::Code);
+    }
+}
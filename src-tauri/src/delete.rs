@@ -0,0 +1,484 @@
+//! Batch deletes: plan first, then execute the plan with its token.
+//!
+//! Planning runs the `safety` checks on every selected path, drops paths
+//! already covered by a selected parent, and keeps the result under a
+//! one-time token. Executing the token trashes the planned items (checking
+//! each again, since the disk may have moved on) and logs them as one batch.
+//!
+//! Logged deletes can be undone from the trash where the platform lets us
+//! list it (freedesktop trash on Linux). Permanent deletes skip the trash and
+//! are only allowed once turned on in settings.
+
+use crate::analysis;
+use crate::cache::{self, DeleteLogEntry, DeleteMode, DeleteRecord};
+use crate::category::CategoryTable;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::index::ScanIndex;
+use crate::platform;
+use crate::safety::{self, DeleteTarget};
+use crate::scanner::ScannerState;
+use crate::settings::ActiveSettings;
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// How long a plan can wait for confirmation
+const PLAN_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Zeros written per call when overwriting
+const OVERWRITE_CHUNK: usize = 1024 * 1024;
+
+/// Slack between the logged delete time and the trash's own record of it
+#[cfg(all(unix, not(target_os = "macos")))]
+const RESTORE_TIME_SLACK_SECS: i64 = 120;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedItem {
+    pub path: String,
+    /// Size measured while planning
+    pub size_bytes: u64,
+}
+
+/// A selected path left out because a selected parent already covers it
+#[derive(Debug, Clone, Serialize)]
+pub struct CollapsedPath {
+    pub path: String,
+    pub covered_by: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletePlan {
+    /// Pass to `execute_delete`; valid once, for a limited time
+    pub token: String,
+    pub scan_path: String,
+    pub items: Vec<PlannedItem>,
+    pub collapsed: Vec<CollapsedPath>,
+    /// Paths refused by the safety checks, each with its error
+    pub violations: Vec<AppError>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteItemResult {
+    pub path: String,
+    pub deleted: bool,
+    pub size_bytes: u64,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteOutcome {
+    /// Batch id shared by the log entries (the plan token)
+    pub batch_id: String,
+    pub results: Vec<DeleteItemResult>,
+    pub deleted_count: usize,
+    pub deleted_bytes: u64,
+}
+
+struct StoredPlan {
+    plan: DeletePlan,
+    created: Instant,
+}
+
+/// Plans waiting for confirmation, keyed by token
+#[derive(Default)]
+pub struct DeletePlans {
+    seq: AtomicU64,
+    plans: Mutex<HashMap<String, StoredPlan>>,
+}
+
+impl DeletePlans {
+    /// Check `paths` and store the resulting plan. Cancelling `cancel`
+    /// abandons the plan.
+    pub fn plan(
+        &self,
+        index: &RwLock<Option<ScanIndex>>,
+        settings: &ActiveSettings,
+        cancel: &Arc<ScannerState>,
+        scan_path: &str,
+        paths: &[String],
+    ) -> AppResult<DeletePlan> {
+        if paths.is_empty() {
+            return Err(AppError::invalid("Nothing selected to delete"));
+        }
+
+        let mut violations = Vec::new();
+        let mut selected: Vec<(PathBuf, &String)> = Vec::with_capacity(paths.len());
+        for path in paths {
+            match safety::normalize(path) {
+                Ok(normalized) => selected.push((normalized, path)),
+                Err(e) => violations.push(e),
+            }
+        }
+        // Parents sort before their children
+        selected.sort();
+        selected.dedup_by(|a, b| a.0 == b.0);
+
+        let mut items: Vec<PlannedItem> = Vec::new();
+        let mut accepted: Vec<PathBuf> = Vec::new();
+        let mut collapsed = Vec::new();
+        for (normalized, path) in selected {
+            if let Some(parent) = accepted.iter().find(|p| normalized.starts_with(p)) {
+                collapsed.push(CollapsedPath {
+                    path: path.clone(),
+                    covered_by: parent.to_string_lossy().to_string(),
+                });
+                continue;
+            }
+            match safety::check_delete(index, settings, cancel, path) {
+                Ok(target) => {
+                    items.push(PlannedItem {
+                        path: target.path.to_string_lossy().to_string(),
+                        size_bytes: target.size_bytes,
+                    });
+                    accepted.push(target.path);
+                }
+                Err(e) if e.code == ErrorCode::Cancelled => return Err(e),
+                Err(e) => violations.push(e),
+            }
+        }
+
+        let plan = DeletePlan {
+            token: self.next_token(),
+            scan_path: scan_path.to_string(),
+            total_bytes: items.iter().map(|i| i.size_bytes).sum(),
+            items,
+            collapsed,
+            violations,
+        };
+        let mut plans = self.plans.lock().unwrap();
+        plans.retain(|_, stored| stored.created.elapsed() < PLAN_TTL);
+        plans.insert(
+            plan.token.clone(),
+            StoredPlan {
+                plan: plan.clone(),
+                created: Instant::now(),
+            },
+        );
+        Ok(plan)
+    }
+
+    /// Take the plan for `token`; each plan can be taken once
+    pub fn take(&self, token: &str) -> AppResult<DeletePlan> {
+        let stored = self.plans.lock().unwrap().remove(token).ok_or_else(|| {
+            AppError::invalid("Unknown or already used delete plan").with_context(token)
+        })?;
+        if stored.created.elapsed() >= PLAN_TTL {
+            return Err(AppError::invalid("Delete plan expired; plan it again").with_context(token));
+        }
+        Ok(stored.plan)
+    }
+
+    fn next_token(&self) -> String {
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(seq);
+        format!("{:016x}{:04x}", hasher.finish(), seq & 0xffff)
+    }
+}
+
+/// Check `path` with the safeguards and move it to the trash, returning
+/// what to log for it
+pub fn trash_one(
+    index: &RwLock<Option<ScanIndex>>,
+    settings: &ActiveSettings,
+    categories: &CategoryTable,
+    cancel: &Arc<ScannerState>,
+    path: &str,
+) -> AppResult<DeleteRecord> {
+    let target = safety::check_delete(index, settings, cancel, path)?;
+    // Described before trashing, while the index still has the item
+    let record = describe(index, categories, &target);
+    trash::delete(&target.path).map_err(|e| {
+        AppError::new(ErrorCode::Io, format!("Failed to move to trash: {}", e)).with_path(path)
+    })?;
+    info!(path, size_bytes = target.size_bytes, "moved to trash");
+    Ok(record)
+}
+
+/// Delete `path` without the trash, after the same safeguards as trashing.
+/// A directory with another filesystem mounted inside it is refused as
+/// well, since removing it recursively would empty that mount.
+///
+/// With `overwrite`, a regular file is filled with zeros and flushed before
+/// it is unlinked. That only reaches the old blocks on filesystems that
+/// write in place, so copy-on-write filesystems are refused, as are
+/// directories, symlinks and files with other hard links (which would see
+/// the zeros).
+pub fn delete_permanently(
+    index: &RwLock<Option<ScanIndex>>,
+    settings: &ActiveSettings,
+    categories: &CategoryTable,
+    cancel: &Arc<ScannerState>,
+    path: &str,
+    overwrite: bool,
+) -> AppResult<DeleteRecord> {
+    if !settings.settings.allow_permanent_delete {
+        return Err(AppError::new(
+            ErrorCode::PermanentDeleteDisabled,
+            "Permanent delete is turned off in settings",
+        )
+        .with_path(path));
+    }
+    let target = safety::check_delete(index, settings, cancel, path)?;
+    let mut record = describe(index, categories, &target);
+    if record.is_dir {
+        safety::check_no_nested_mounts(&target.path)?;
+    }
+
+    if overwrite {
+        overwrite_file(&target.path)?;
+        record.mode = DeleteMode::Overwrite;
+    } else {
+        record.mode = DeleteMode::Permanent;
+    }
+    let removed = if record.is_dir {
+        std::fs::remove_dir_all(&target.path)
+    } else {
+        std::fs::remove_file(&target.path)
+    };
+    removed.map_err(|e| AppError::io("Failed to delete", &e).with_path(path))?;
+    info!(
+        path,
+        mode = record.mode.as_str(),
+        size_bytes = record.size_bytes,
+        "deleted permanently"
+    );
+    Ok(record)
+}
+
+/// Replace a regular file's contents with zeros, on disk
+fn overwrite_file(path: &Path) -> AppResult<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+
+    let meta = std::fs::symlink_metadata(path)
+        .map_err(|e| AppError::io("Failed to read target", &e).with_path(path))?;
+    if !meta.file_type().is_file() {
+        return Err(AppError::invalid("Only regular files can be overwritten").with_path(path));
+    }
+    if meta.nlink() > 1 {
+        return Err(AppError::invalid(
+            "File has other hard links; overwriting would change them too",
+        )
+        .with_path(path));
+    }
+    if let Some(fs_type) = platform::copy_on_write_filesystem(path) {
+        return Err(AppError::new(
+            ErrorCode::Unsupported,
+            format!(
+                "Overwriting can't reach the old data on {}; delete without overwrite",
+                fs_type
+            ),
+        )
+        .with_path(path));
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)
+        .map_err(|e| AppError::io("Failed to open for overwrite", &e).with_path(path))?;
+    let zeros = vec![0u8; OVERWRITE_CHUNK];
+    let mut remaining = meta.len();
+    while remaining > 0 {
+        let n = remaining.min(OVERWRITE_CHUNK as u64) as usize;
+        file.write_all(&zeros[..n])
+            .map_err(|e| AppError::io("Failed to overwrite", &e).with_path(path))?;
+        remaining -= n as u64;
+    }
+    file.sync_all()
+        .map_err(|e| AppError::io("Failed to flush overwrite", &e).with_path(path))
+}
+
+/// Type and item counts of a checked target, from the index
+fn describe(
+    index: &RwLock<Option<ScanIndex>>,
+    categories: &CategoryTable,
+    target: &DeleteTarget,
+) -> DeleteRecord {
+    let mut record = DeleteRecord {
+        target_path: target.path.to_string_lossy().to_string(),
+        size_bytes: target.size_bytes,
+        is_dir: false,
+        extension: None,
+        category: None,
+        file_count: 1,
+        dir_count: 0,
+        mode: DeleteMode::Trash,
+    };
+    let guard = index.read().unwrap();
+    let Some((index, id)) = guard
+        .as_ref()
+        .and_then(|index| Some((index, index.find(&target.path)?)))
+    else {
+        return record;
+    };
+    let Some(node) = index.get(id) else {
+        return record;
+    };
+    if node.is_dir {
+        record.is_dir = true;
+        record.file_count = node.file_count;
+        record.dir_count = node.dir_count + 1;
+        record.category = analysis::type_stats(&analysis::extension_stats(index, id), categories)
+            .by_category
+            .first()
+            .map(|c| c.category);
+    } else {
+        record.extension = node.extension.clone();
+        record.category = Some(categories.category_of(node.extension.as_deref()));
+    }
+    record
+}
+
+/// Trash every item of `plan`, checking each again first, and log the
+/// deleted ones as one batch. Items not reached before `cancel` is
+/// cancelled are left in place.
+pub fn execute(
+    index: &RwLock<Option<ScanIndex>>,
+    settings: &ActiveSettings,
+    categories: &CategoryTable,
+    cancel: &Arc<ScannerState>,
+    current_scan_path: Option<&str>,
+    plan: DeletePlan,
+) -> AppResult<DeleteOutcome> {
+    if current_scan_path != Some(plan.scan_path.as_str()) {
+        return Err(AppError::new(
+            ErrorCode::NoScan,
+            "The scan changed since the delete was planned; plan it again",
+        )
+        .with_path(&plan.scan_path));
+    }
+
+    let mut results = Vec::with_capacity(plan.items.len());
+    let mut records = Vec::with_capacity(plan.items.len());
+    for item in plan.items {
+        let trashed = if cancel.is_cancelled() {
+            Err(AppError::new(ErrorCode::Cancelled, "Cancelled before deleting")
+                .with_path(&item.path))
+        } else {
+            trash_one(index, settings, categories, cancel, &item.path)
+        };
+        results.push(match trashed {
+            Ok(record) => {
+                let result = DeleteItemResult {
+                    path: item.path,
+                    deleted: true,
+                    size_bytes: record.size_bytes,
+                    error: None,
+                };
+                records.push(record);
+                result
+            }
+            Err(e) => {
+                warn!(path = %item.path, error = %e, "batch delete item failed");
+                DeleteItemResult {
+                    path: item.path,
+                    deleted: false,
+                    size_bytes: item.size_bytes,
+                    error: Some(e),
+                }
+            }
+        });
+    }
+
+    let deleted_count = records.len();
+    let deleted_bytes = records.iter().map(|r| r.size_bytes).sum();
+    if let Err(e) = cache::log_delete_batch(&plan.scan_path, &plan.token, &records) {
+        warn!(error = %e, "failed to log delete batch");
+    }
+    info!(
+        batch_id = %plan.token,
+        deleted = deleted_count,
+        failed = results.len() - deleted_count,
+        bytes = deleted_bytes,
+        "delete batch finished"
+    );
+
+    Ok(DeleteOutcome {
+        batch_id: plan.token,
+        results,
+        deleted_count,
+        deleted_bytes,
+    })
+}
+
+/// Put a logged delete back from the trash and mark the entry restored.
+///
+/// The trash item is the one with the entry's original path whose deletion
+/// time is closest to the logged one.
+pub fn restore(log_id: u64) -> AppResult<DeleteLogEntry> {
+    let entry = cache::get_delete_entry(log_id)?
+        .ok_or_else(|| AppError::invalid(format!("No delete log entry {}", log_id)))?;
+    if entry.restored_at.is_some() {
+        return Err(AppError::invalid("Already restored").with_path(&entry.target_path));
+    }
+    if entry.mode != DeleteMode::Trash {
+        return Err(AppError::invalid("Deleted permanently; nothing to restore")
+            .with_path(&entry.target_path));
+    }
+    if std::fs::symlink_metadata(&entry.target_path).is_ok() {
+        return Err(AppError::invalid(format!(
+            "Something already exists at {}",
+            entry.target_path
+        ))
+        .with_path(&entry.target_path));
+    }
+
+    restore_from_trash(&entry)?;
+    cache::mark_restored(log_id)?;
+    info!(path = %entry.target_path, log_id, "restored from trash");
+    cache::get_delete_entry(log_id)?
+        .ok_or_else(|| AppError::internal(format!("Delete log entry {} vanished", log_id)))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn restore_from_trash(entry: &DeleteLogEntry) -> AppResult<()> {
+    let items = trash::os_limited::list()
+        .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to list the trash: {}", e)))?;
+    let deleted_at = entry.deleted_at as i64;
+    // A negative time means the trash didn't record one
+    let item = items
+        .into_iter()
+        .filter(|item| item.original_path() == Path::new(&entry.target_path))
+        .filter(|item| {
+            item.time_deleted < 0
+                || (item.time_deleted - deleted_at).abs() <= RESTORE_TIME_SLACK_SECS
+        })
+        .min_by_key(|item| {
+            (
+                item.time_deleted < 0,
+                (item.time_deleted - deleted_at).abs(),
+            )
+        })
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorCode::NotFound,
+                format!("No longer in the trash: {}", entry.target_path),
+            )
+            .with_path(&entry.target_path)
+        })?;
+    trash::os_limited::restore_all([item]).map_err(|e| {
+        AppError::new(
+            ErrorCode::Io,
+            format!("Failed to restore from the trash: {}", e),
+        )
+        .with_path(&entry.target_path)
+    })
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn restore_from_trash(entry: &DeleteLogEntry) -> AppResult<()> {
+    Err(AppError::new(
+        ErrorCode::Unsupported,
+        "Restoring from the trash isn't supported on this platform",
+    )
+    .with_path(&entry.target_path))
+}
@@ -0,0 +1,144 @@
+//! Error type shared by the commands and the modules behind them.
+//!
+//! Errors reach the frontend as `{ code, message, path?, context? }`. The
+//! code is stable, so the UI can branch on it (offer a rescan on a cache
+//! version mismatch, say) instead of matching message text.
+
+use serde::Serialize;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The path doesn't exist
+    NotFound,
+    NotADirectory,
+    PermissionDenied,
+    /// Another scan of the same path is running
+    ScanInProgress,
+    /// The command needs a loaded scan
+    NoScan,
+    /// The path isn't part of the live or cached index
+    NotIndexed,
+    /// Nothing cached for the path
+    CacheMiss,
+    /// The cache was written by an incompatible version; rescan
+    CacheVersionMismatch,
+    /// The cached data couldn't be decoded; rescan
+    CacheCorrupt,
+    CacheTooLarge,
+    /// Deleting a built-in or user-configured protected location
+    ProtectedPath,
+    /// Deleting something outside the current scan's roots
+    OutsideScanRoot,
+    /// Deleting a mount point
+    MountPoint,
+    /// The target's size moved too far from what the scan recorded; rescan
+    SizeChanged,
+    /// Permanent delete is turned off in settings
+    PermanentDeleteDisabled,
+    /// The operation was cancelled before it finished
+    Cancelled,
+    /// Arguments failed validation
+    InvalidInput,
+    /// The operation isn't available for this scan (e.g. a workspace)
+    Unsupported,
+    Database,
+    Io,
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// What was being attempted, when the message alone doesn't say
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            path: None,
+            context: None,
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// A database failure, described as "<what>: <error>"
+    pub fn database(what: &str, err: impl fmt::Display) -> Self {
+        Self::new(ErrorCode::Database, format!("{}: {}", what, err))
+    }
+
+    /// An IO failure, with the code taken from the error kind
+    pub fn io(what: &str, err: &io::Error) -> Self {
+        let code = match err.kind() {
+            io::ErrorKind::NotFound => ErrorCode::NotFound,
+            io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            _ => ErrorCode::Io,
+        };
+        Self::new(code, format!("{}: {}", what, err))
+    }
+
+    pub fn not_found(path: &str) -> Self {
+        Self::new(
+            ErrorCode::NotFound,
+            format!("Path does not exist: {}", path),
+        )
+        .with_path(path)
+    }
+
+    /// Cached data that can't be used as is; a fresh scan replaces it
+    pub fn is_stale_cache(&self) -> bool {
+        matches!(
+            self.code,
+            ErrorCode::CacheVersionMismatch | ErrorCode::CacheCorrupt
+        )
+    }
+
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_string_lossy().to_string());
+        self
+    }
+
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::time::SystemTimeError> for AppError {
+    fn from(err: std::time::SystemTimeError) -> Self {
+        Self::internal(format!("Time error: {}", err))
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        Self::internal(format!("Background task failed: {}", err))
+    }
+}
@@ -0,0 +1,733 @@
+//! Full-resolution scan index.
+//!
+//! The UI tree is capped at `MAX_TOTAL_NODES` and folds overflow into
+//! `__other__` placeholders, so it can't answer questions about the whole
+//! scan. The index keeps every walked entry in a flat arena with parent
+//! links; paths are rebuilt from names on demand to keep it compact.
+//!
+//! It is also the live tree behind incremental refreshes: children are kept
+//! sorted by name so a path resolves with one binary search per component,
+//! and every directory carries its subtree totals so replacing a subtree only
+//! touches the nodes on the path up to the root.
+//!
+//! A workspace index (several scan roots under one synthetic root) has the
+//! workspace key as its root path, and each top-level child is named by its
+//! absolute path, so path rebuilding works unchanged.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::scanner::FileNode;
+use crate::workspace::WORKSPACE_PREFIX;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+pub type NodeId = u32;
+
+/// Root node is always the first slot
+pub const ROOT_ID: NodeId = 0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexNode {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    /// Sorted by name
+    pub children: Vec<NodeId>,
+    pub size: u64,
+    pub is_dir: bool,
+    pub extension: Option<String>,
+    pub modified_at: Option<u64>,
+    pub accessed_at: Option<u64>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    /// Files below this directory (0 for files)
+    pub file_count: u64,
+    /// Directories below this directory, excluding itself (0 for files)
+    pub dir_count: u64,
+}
+
+impl IndexNode {
+    /// What this node adds to its parent's (size, files, dirs) totals
+    fn contribution(&self) -> (u64, u64, u64) {
+        if self.is_dir {
+            (self.size, self.file_count, self.dir_count + 1)
+        } else {
+            (self.size, 1, 0)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanIndex {
+    root_path: String,
+    nodes: Vec<Option<IndexNode>>,
+    free: Vec<NodeId>,
+}
+
+impl ScanIndex {
+    pub fn with_capacity(root_path: &Path, capacity: usize) -> Self {
+        Self {
+            root_path: root_path.to_string_lossy().to_string(),
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+        }
+    }
+
+    /// Whether the root is a synthetic workspace node
+    pub fn is_workspace(&self) -> bool {
+        self.root_path.starts_with(WORKSPACE_PREFIX)
+    }
+
+    /// Directories the scan walked: the root, or a workspace's top-level roots
+    pub fn scan_roots(&self) -> Vec<PathBuf> {
+        if !self.is_workspace() {
+            return vec![PathBuf::from(&self.root_path)];
+        }
+        self.get(ROOT_ID)
+            .map(|root| root.children.iter().map(|&c| PathBuf::from(self.name_of(c))).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether `path` falls under this scan (not whether it is indexed)
+    pub fn contains_path(&self, path: &Path) -> bool {
+        if path == Path::new(&self.root_path) {
+            return true;
+        }
+        if !self.is_workspace() {
+            return path.starts_with(&self.root_path);
+        }
+        self.get(ROOT_ID).is_some_and(|root| {
+            root.children
+                .iter()
+                .any(|&c| path.starts_with(self.name_of(c)))
+        })
+    }
+
+    /// Number of live nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&IndexNode> {
+        self.nodes.get(id as usize).and_then(|n| n.as_ref())
+    }
+
+    /// Insert a node under `parent` (or as the root when `parent` is None).
+    ///
+    /// Ancestor totals are left untouched; builders call `recompute_totals`
+    /// once everything is inserted. Inserting siblings in name order keeps
+    /// this an append.
+    pub fn insert(&mut self, parent: Option<NodeId>, mut node: IndexNode) -> NodeId {
+        node.parent = parent;
+        node.children.clear();
+
+        let position = parent.and_then(|p| {
+            let siblings = &self.get(p)?.children;
+            Some(
+                siblings
+                    .binary_search_by(|&c| self.name_of(c).cmp(node.name.as_str()))
+                    .unwrap_or_else(|pos| pos),
+            )
+        });
+
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id as usize] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                (self.nodes.len() - 1) as NodeId
+            }
+        };
+
+        if let (Some(p), Some(pos)) = (parent, position) {
+            if let Some(parent_node) = self.nodes[p as usize].as_mut() {
+                parent_node.children.insert(pos, id);
+            }
+        }
+
+        id
+    }
+
+    /// Recompute directory sizes and counts bottom-up from the leaves
+    pub fn recompute_totals(&mut self) {
+        let order: Vec<NodeId> = self.descendants(ROOT_ID).collect();
+        for &id in order.iter().rev() {
+            let Some(node) = self.get(id) else { continue };
+            if !node.is_dir {
+                continue;
+            }
+            let (size, files, dirs) = node
+                .children
+                .iter()
+                .filter_map(|&c| self.get(c))
+                .map(IndexNode::contribution)
+                .fold((0, 0, 0), |acc, c| (acc.0 + c.0, acc.1 + c.1, acc.2 + c.2));
+            if let Some(node) = self.nodes[id as usize].as_mut() {
+                node.size = size;
+                node.file_count = files;
+                node.dir_count = dirs;
+            }
+        }
+    }
+
+    /// Rebuild the absolute path of a node from its ancestor names
+    pub fn path_of(&self, id: NodeId) -> PathBuf {
+        let mut names: Vec<&str> = Vec::new();
+        let mut current = Some(id);
+        while let Some(cur) = current {
+            let Some(node) = self.get(cur) else { break };
+            if node.parent.is_none() {
+                break;
+            }
+            names.push(&node.name);
+            current = node.parent;
+        }
+
+        let mut path = PathBuf::from(&self.root_path);
+        for name in names.iter().rev() {
+            path.push(name);
+        }
+        path
+    }
+
+    /// Look up a node by absolute path (one binary search per component)
+    pub fn find(&self, path: &Path) -> Option<NodeId> {
+        if self.is_workspace() && path != Path::new(&self.root_path) {
+            // Real paths resolve through the top-level root they fall under
+            let root = self.get(ROOT_ID)?;
+            return root.children.iter().find_map(|&top| {
+                let rel = path.strip_prefix(self.name_of(top)).ok()?;
+                self.find_from(top, rel)
+            });
+        }
+        let rel = path.strip_prefix(&self.root_path).ok()?;
+        self.find_from(ROOT_ID, rel)
+    }
+
+    /// The directory to rescan so `path` ends up indexed: `path` itself when
+    /// it is indexed, else its topmost missing ancestor, whose parent is
+    /// indexed so `replace_subtree` can insert it. None when nothing above
+    /// `path` is indexed.
+    pub fn rescan_target(&self, path: &Path) -> Option<PathBuf> {
+        let mut target = path;
+        for ancestor in path.ancestors() {
+            if self.find(ancestor).is_some() {
+                return Some(target.to_path_buf());
+            }
+            target = ancestor;
+        }
+        None
+    }
+
+    /// Resolve an optional subtree path (None means the scan root)
+    pub fn resolve(&self, path: Option<&str>) -> AppResult<NodeId> {
+        match path {
+            Some(p) => self
+                .find(Path::new(p))
+                .ok_or_else(|| {
+                    AppError::new(ErrorCode::NotIndexed, format!("Path not in scan index: {}", p))
+                        .with_path(p)
+                }),
+            None => Ok(ROOT_ID),
+        }
+    }
+
+    /// Pre-order iterator over a node and all of its descendants
+    pub fn descendants(&self, id: NodeId) -> Descendants<'_> {
+        let stack = if self.get(id).is_some() { vec![id] } else { Vec::new() };
+        Descendants { index: self, stack }
+    }
+
+    /// Replace (or insert) the subtree at `path` with the root of `subtree`.
+    ///
+    /// Only the ancestors of `path` are re-aggregated, by the difference in
+    /// size and counts. Returns false when neither the path nor its parent is
+    /// present in the index.
+    pub fn replace_subtree(&mut self, path: &Path, subtree: ScanIndex) -> bool {
+        if path == Path::new(&self.root_path) {
+            *self = subtree;
+            return true;
+        }
+
+        let Some(parent_id) = path.parent().and_then(|p| self.find(p)) else {
+            return false;
+        };
+        let Some(new) = subtree.get(ROOT_ID).map(IndexNode::contribution) else {
+            return false;
+        };
+
+        let mut old = (0, 0, 0);
+        if let Some(existing) = self.find(path) {
+            old = self.get(existing).map(IndexNode::contribution).unwrap_or(old);
+            self.detach(existing);
+        }
+
+        self.graft(parent_id, &subtree, ROOT_ID);
+        self.adjust_ancestors(parent_id, old, new);
+        true
+    }
+
+    /// Remove the node at `path` and its descendants, re-aggregating its
+    /// ancestors. The scan root itself can't be removed.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let Some(id) = self.find(path) else { return false };
+        let Some(node) = self.get(id) else { return false };
+        let Some(parent) = node.parent else { return false };
+
+        let old = node.contribution();
+        self.detach(id);
+        self.adjust_ancestors(parent, old, (0, 0, 0));
+        true
+    }
+
+    /// Move the subtree at `from` to `to` (a rename), replacing whatever was
+    /// indexed at `to`. Returns false when `from` or the parent of `to` isn't
+    /// indexed, leaving the index untouched.
+    pub fn move_node(&mut self, from: &Path, to: &Path) -> bool {
+        if to.starts_with(from) || from.starts_with(to) {
+            return from == to;
+        }
+        let Some(id) = self.find(from) else { return false };
+        let Some(old_parent) = self.get(id).and_then(|n| n.parent) else { return false };
+        let Some(new_parent) = to.parent().and_then(|p| self.find(p)) else { return false };
+        let Some(name) = to.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            return false;
+        };
+
+        self.remove(to);
+
+        let contribution = self.get(id).map(IndexNode::contribution).unwrap_or_default();
+        if let Some(parent_node) = self.nodes[old_parent as usize].as_mut() {
+            parent_node.children.retain(|&c| c != id);
+        }
+        self.adjust_ancestors(old_parent, contribution, (0, 0, 0));
+
+        let position = self
+            .get(new_parent)
+            .map(|p| {
+                p.children
+                    .binary_search_by(|&c| self.name_of(c).cmp(name.as_str()))
+                    .unwrap_or_else(|pos| pos)
+            })
+            .unwrap_or(0);
+        if let Some(node) = self.nodes[id as usize].as_mut() {
+            if !node.is_dir {
+                node.extension = Path::new(&name)
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_lowercase());
+            }
+            node.name = name;
+            node.parent = Some(new_parent);
+        }
+        if let Some(parent_node) = self.nodes[new_parent as usize].as_mut() {
+            parent_node.children.insert(position, id);
+        }
+        self.adjust_ancestors(new_parent, (0, 0, 0), contribution);
+        true
+    }
+
+    /// Materialize the capped UI tree for a node.
+    ///
+    /// Children are ordered largest first; anything beyond `max_depth` or the
+    /// `max_nodes` budget is folded into a `__other__` placeholder.
+    pub fn to_file_node(
+        &self,
+        id: NodeId,
+        max_depth: usize,
+        max_nodes: usize,
+        node_count: &mut usize,
+    ) -> Option<FileNode> {
+        self.build_file_node(id, self.path_of(id), 0, max_depth, max_nodes, node_count)
+    }
+
+    /// Build an index from a (possibly truncated) UI tree.
+    ///
+    /// Used for caches written before the index was persisted; entries folded
+    /// into `__other__` placeholders are not recoverable, but directory totals
+    /// are copied from the tree so they still include them.
+    pub fn from_file_node(root: &FileNode) -> Self {
+        let mut index = Self::with_capacity(Path::new(&root.path), 1024);
+        let mut stack: Vec<(&FileNode, Option<NodeId>)> = vec![(root, None)];
+
+        while let Some((node, parent)) = stack.pop() {
+            if node.id.ends_with("/__other__") {
+                continue;
+            }
+            let id = index.insert(
+                parent,
+                IndexNode {
+                    name: node.name.clone(),
+                    size: node.size,
+                    is_dir: node.is_dir,
+                    extension: node.extension.clone(),
+                    modified_at: node.modified_at,
+                    accessed_at: node.accessed_at,
+                    uid: node.uid,
+                    gid: node.gid,
+                    file_count: node.file_count,
+                    dir_count: node.dir_count,
+                    ..Default::default()
+                },
+            );
+            let mut children: Vec<&FileNode> = node.children.iter().collect();
+            children.sort_unstable_by(|a, b| b.name.cmp(&a.name));
+            for child in children {
+                stack.push((child, Some(id)));
+            }
+        }
+
+        index
+    }
+
+    fn name_of(&self, id: NodeId) -> &str {
+        self.get(id).map(|n| n.name.as_str()).unwrap_or("")
+    }
+
+    /// Walk `rel` down from `start`, one binary search per component
+    fn find_from(&self, start: NodeId, rel: &Path) -> Option<NodeId> {
+        let mut current = start;
+        self.get(current)?;
+
+        for component in rel.components() {
+            let name = component.as_os_str().to_string_lossy();
+            current = self.child_by_name(current, &name)?;
+        }
+
+        Some(current)
+    }
+
+    fn child_by_name(&self, parent: NodeId, name: &str) -> Option<NodeId> {
+        let children = &self.get(parent)?.children;
+        let pos = children
+            .binary_search_by(|&c| self.name_of(c).cmp(name))
+            .ok()?;
+        Some(children[pos])
+    }
+
+    /// Remove a node and its descendants, unlinking it from its parent
+    fn detach(&mut self, id: NodeId) {
+        let parent = self.get(id).and_then(|n| n.parent);
+        if let Some(parent_node) = parent.and_then(|p| self.nodes[p as usize].as_mut()) {
+            parent_node.children.retain(|&c| c != id);
+        }
+
+        let ids: Vec<NodeId> = self.descendants(id).collect();
+        for removed in ids {
+            self.nodes[removed as usize] = None;
+            self.free.push(removed);
+        }
+    }
+
+    /// Copy `from` (and its descendants) out of `source` under `parent`
+    fn graft(&mut self, parent: NodeId, source: &ScanIndex, from: NodeId) {
+        let mut stack: Vec<(NodeId, NodeId)> = vec![(from, parent)];
+        while let Some((src_id, dst_parent)) = stack.pop() {
+            let Some(src) = source.get(src_id) else { continue };
+            let id = self.insert(Some(dst_parent), src.clone());
+            // Reversed so siblings pop (and insert) in name order
+            for &child in src.children.iter().rev() {
+                stack.push((child, id));
+            }
+        }
+    }
+
+    /// Apply a (size, files, dirs) change to `from` and every ancestor
+    fn adjust_ancestors(&mut self, from: NodeId, old: (u64, u64, u64), new: (u64, u64, u64)) {
+        let mut current = Some(from);
+        while let Some(id) = current {
+            let Some(node) = self.nodes[id as usize].as_mut() else { break };
+            node.size = (node.size + new.0).saturating_sub(old.0);
+            node.file_count = (node.file_count + new.1).saturating_sub(old.1);
+            node.dir_count = (node.dir_count + new.2).saturating_sub(old.2);
+            current = node.parent;
+        }
+    }
+
+    fn build_file_node(
+        &self,
+        id: NodeId,
+        path: PathBuf,
+        depth: usize,
+        max_depth: usize,
+        max_nodes: usize,
+        node_count: &mut usize,
+    ) -> Option<FileNode> {
+        if *node_count >= max_nodes {
+            return None;
+        }
+        let node = self.get(id)?;
+        let path_str = path.to_string_lossy().to_string();
+        *node_count += 1;
+
+        let mut children = Vec::new();
+        if node.is_dir {
+            let mut sorted: Vec<NodeId> = node.children.clone();
+            sorted.sort_by_key(|&c| Reverse(self.get(c).map(|n| n.size).unwrap_or(0)));
+
+            let mut other = (0u64, 0u64, 0u64);
+            for child_id in sorted {
+                let Some(child) = self.get(child_id) else { continue };
+                let built = if depth < max_depth && *node_count < max_nodes {
+                    self.build_file_node(
+                        child_id,
+                        path.join(&child.name),
+                        depth + 1,
+                        max_depth,
+                        max_nodes,
+                        node_count,
+                    )
+                } else {
+                    None
+                };
+                match built {
+                    Some(child_node) => children.push(child_node),
+                    None => {
+                        let (size, files, dirs) = child.contribution();
+                        other = (other.0 + size, other.1 + files, other.2 + dirs);
+                    }
+                }
+            }
+
+            if other.1 + other.2 > 0 {
+                children.push(FileNode {
+                    id: format!("{}/__other__", path_str),
+                    name: format!("<{} more items>", other.1 + other.2),
+                    path: path_str.clone(),
+                    size: other.0,
+                    is_dir: true,
+                    children: vec![],
+                    extension: None,
+                    file_count: other.1,
+                    dir_count: other.2,
+                    modified_at: None,
+                    accessed_at: None,
+                    uid: None,
+                    gid: None,
+                });
+            }
+        }
+
+        Some(FileNode {
+            id: path_str.clone(),
+            name: node.name.clone(),
+            path: path_str,
+            size: node.size,
+            is_dir: node.is_dir,
+            children,
+            extension: node.extension.clone(),
+            file_count: node.file_count,
+            dir_count: node.dir_count,
+            modified_at: node.modified_at,
+            accessed_at: node.accessed_at,
+            uid: node.uid,
+            gid: node.gid,
+        })
+    }
+}
+
+pub struct Descendants<'a> {
+    index: &'a ScanIndex,
+    stack: Vec<NodeId>,
+}
+
+impl Iterator for Descendants<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.stack.pop()?;
+        if let Some(node) = self.index.get(id) {
+            self.stack.extend(node.children.iter().rev());
+        }
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64) -> IndexNode {
+        IndexNode {
+            name: name.to_string(),
+            size,
+            extension: Path::new(name)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase()),
+            ..Default::default()
+        }
+    }
+
+    fn dir(name: &str) -> IndexNode {
+        IndexNode {
+            name: name.to_string(),
+            is_dir: true,
+            ..Default::default()
+        }
+    }
+
+    /// /r/{a/{b/x.iso, y.txt}, c/z.rs}
+    fn fixture() -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 8);
+        let root = index.insert(None, dir("r"));
+        let a = index.insert(Some(root), dir("a"));
+        let b = index.insert(Some(a), dir("b"));
+        index.insert(Some(b), file("x.iso", 5000));
+        index.insert(Some(a), file("y.txt", 100));
+        let c = index.insert(Some(root), dir("c"));
+        index.insert(Some(c), file("z.rs", 300));
+        index.recompute_totals();
+        index
+    }
+
+    fn totals(index: &ScanIndex, path: &str) -> (u64, u64, u64) {
+        let node = index.get(index.find(Path::new(path)).unwrap()).unwrap();
+        (node.size, node.file_count, node.dir_count)
+    }
+
+    fn children(index: &ScanIndex, path: &str) -> Vec<String> {
+        let node = index.get(index.find(Path::new(path)).unwrap()).unwrap();
+        node.children
+            .iter()
+            .map(|&c| index.get(c).unwrap().name.clone())
+            .collect()
+    }
+
+    /// Incremental totals must agree with a full recomputation
+    fn assert_consistent(index: &ScanIndex) {
+        let mut recomputed = index.clone();
+        recomputed.recompute_totals();
+        for id in index.descendants(ROOT_ID) {
+            let (a, b) = (index.get(id).unwrap(), recomputed.get(id).unwrap());
+            assert_eq!(
+                (a.size, a.file_count, a.dir_count),
+                (b.size, b.file_count, b.dir_count),
+                "{}",
+                index.path_of(id).display()
+            );
+        }
+    }
+
+    #[test]
+    fn find_and_path_round_trip() {
+        let index = fixture();
+        assert_eq!(index.len(), 7);
+        let id = index.find(Path::new("/r/a/b/x.iso")).unwrap();
+        assert_eq!(index.path_of(id), PathBuf::from("/r/a/b/x.iso"));
+        assert!(index.find(Path::new("/r/a/missing")).is_none());
+        assert!(index.find(Path::new("/elsewhere")).is_none());
+        assert_eq!(totals(&index, "/r"), (5400, 3, 3));
+    }
+
+    #[test]
+    fn replace_subtree_updates_only_ancestors() {
+        let mut index = fixture();
+        let mut subtree = ScanIndex::with_capacity(Path::new("/r/a/b"), 4);
+        let root = subtree.insert(None, dir("b"));
+        subtree.insert(Some(root), file("new.bin", 700));
+        subtree.insert(Some(root), file("more.bin", 50));
+        subtree.recompute_totals();
+
+        assert!(index.replace_subtree(Path::new("/r/a/b"), subtree));
+        assert_eq!(totals(&index, "/r/a/b"), (750, 2, 0));
+        assert_eq!(totals(&index, "/r/a"), (850, 3, 1));
+        assert_eq!(totals(&index, "/r"), (1150, 4, 3));
+        assert!(index.find(Path::new("/r/a/b/x.iso")).is_none());
+        assert_eq!(children(&index, "/r/a/b"), vec!["more.bin", "new.bin"]);
+        assert_eq!(index.len(), 8);
+        assert_consistent(&index);
+    }
+
+    #[test]
+    fn replace_subtree_inserts_new_dirs_and_rejects_orphans() {
+        let mut index = fixture();
+        let mut subtree = ScanIndex::with_capacity(Path::new("/r/b"), 2);
+        let root = subtree.insert(None, dir("b"));
+        subtree.insert(Some(root), file("w", 10));
+        subtree.recompute_totals();
+
+        assert!(!index.replace_subtree(Path::new("/r/none/b"), subtree.clone()));
+        assert!(index.replace_subtree(Path::new("/r/b"), subtree));
+        assert_eq!(children(&index, "/r"), vec!["a", "b", "c"]);
+        assert_eq!(totals(&index, "/r"), (5410, 4, 4));
+        assert_consistent(&index);
+    }
+
+    #[test]
+    fn nested_new_dirs_are_inserted_at_the_topmost_missing_one() {
+        let mut index = fixture();
+        assert_eq!(index.rescan_target(Path::new("/r/a/b")), Some(PathBuf::from("/r/a/b")));
+        // `mkdir -p /r/a/n/m/k` in one burst
+        let target = index.rescan_target(Path::new("/r/a/n/m/k")).unwrap();
+        assert_eq!(target, PathBuf::from("/r/a/n"));
+        // Directly under the root, only the new dir is rescanned
+        assert_eq!(index.rescan_target(Path::new("/r/new")), Some(PathBuf::from("/r/new")));
+        assert_eq!(index.rescan_target(Path::new("/elsewhere/x")), None);
+
+        let mut subtree = ScanIndex::with_capacity(&target, 4);
+        let n = subtree.insert(None, dir("n"));
+        let m = subtree.insert(Some(n), dir("m"));
+        let k = subtree.insert(Some(m), dir("k"));
+        subtree.insert(Some(k), file("leaf.bin", 40));
+        subtree.recompute_totals();
+
+        assert!(index.replace_subtree(&target, subtree));
+        assert!(index.find(Path::new("/r/a/n/m/k/leaf.bin")).is_some());
+        assert_eq!(totals(&index, "/r/a"), (5140, 3, 4));
+        assert_eq!(totals(&index, "/r"), (5440, 4, 6));
+        assert_consistent(&index);
+    }
+
+    #[test]
+    fn remove_reaggregates_and_frees_slots() {
+        let mut index = fixture();
+        assert!(index.remove(Path::new("/r/a/b")));
+        assert_eq!(totals(&index, "/r/a"), (100, 1, 0));
+        assert_eq!(totals(&index, "/r"), (400, 2, 2));
+        assert_eq!(index.len(), 5);
+        assert!(!index.remove(Path::new("/r/a/b")));
+        // The root can't be removed
+        assert!(!index.remove(Path::new("/r")));
+        assert_consistent(&index);
+
+        // Freed slots are reused
+        let c = index.find(Path::new("/r/c")).unwrap();
+        index.insert(Some(c), file("q.rs", 1));
+        assert_eq!(index.len(), 6);
+        assert_eq!(index.nodes.len(), 7);
+    }
+
+    #[test]
+    fn move_node_renames_across_parents() {
+        let mut index = fixture();
+        assert!(index.move_node(Path::new("/r/a/y.txt"), Path::new("/r/c/y.md")));
+        assert!(index.find(Path::new("/r/a/y.txt")).is_none());
+        let id = index.find(Path::new("/r/c/y.md")).unwrap();
+        assert_eq!(index.get(id).unwrap().extension.as_deref(), Some("md"));
+        assert_eq!(children(&index, "/r/c"), vec!["y.md", "z.rs"]);
+        assert_eq!(totals(&index, "/r/a"), (5000, 1, 1));
+        assert_eq!(totals(&index, "/r/c"), (400, 2, 0));
+        assert_eq!(totals(&index, "/r"), (5400, 3, 3));
+        assert_consistent(&index);
+    }
+
+    #[test]
+    fn move_node_replaces_target_and_rejects_bad_moves() {
+        let mut index = fixture();
+        // Moving a directory onto an existing one replaces it
+        assert!(index.move_node(Path::new("/r/a/b"), Path::new("/r/c")));
+        assert_eq!(children(&index, "/r"), vec!["a", "c"]);
+        assert_eq!(children(&index, "/r/c"), vec!["x.iso"]);
+        assert_eq!(totals(&index, "/r"), (5100, 2, 2));
+        assert_eq!(index.len(), 5);
+        assert_consistent(&index);
+
+        let before = index.len();
+        assert!(!index.move_node(Path::new("/r/missing"), Path::new("/r/m")));
+        assert!(!index.move_node(Path::new("/r/a"), Path::new("/r/none/a")));
+        assert!(!index.move_node(Path::new("/r/a"), Path::new("/r/a/inner")));
+        assert!(index.move_node(Path::new("/r/a"), Path::new("/r/a")));
+        assert_eq!(index.len(), before);
+        assert_consistent(&index);
+    }
+}
@@ -0,0 +1,213 @@
+//! Scan job bookkeeping.
+//!
+//! Every scan (a user-started full scan or a background incremental refresh)
+//! runs as a job with its own id and cancellation token, so several scans
+//! can run at once and each can be cancelled on its own.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::scanner::ScannerState;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub type JobId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanJobKind {
+    /// A scan started by the user
+    Full,
+    /// A watcher-driven incremental refresh
+    Refresh,
+    /// Re-measuring directories before they are deleted
+    Measure,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanJobInfo {
+    pub job_id: JobId,
+    pub kind: ScanJobKind,
+    pub path: String,
+    pub started_at: u64,
+    pub cancelled: bool,
+    pub paused: bool,
+}
+
+struct ScanJob {
+    info: ScanJobInfo,
+    state: Arc<ScannerState>,
+}
+
+impl ScanJob {
+    fn pause(&mut self, paused: bool) {
+        if paused {
+            self.state.pause();
+        } else {
+            self.state.resume();
+        }
+        self.info.paused = paused;
+    }
+}
+
+#[derive(Default)]
+pub struct ScanManager {
+    next_id: AtomicU64,
+    /// Id of the most recently started full scan
+    latest_full: AtomicU64,
+    jobs: Mutex<HashMap<JobId, ScanJob>>,
+}
+
+impl ScanManager {
+    /// Register a new job for `path`.
+    ///
+    /// A full scan is refused while another full scan of the same path runs,
+    /// and cancels any refresh of it. A refresh is refused while any other
+    /// job for the path runs. Measurements only read the disk, so they run
+    /// alongside anything.
+    pub fn start(self: &Arc<Self>, kind: ScanJobKind, path: &str) -> AppResult<ScanJobHandle> {
+        let mut jobs = self.jobs.lock().unwrap();
+        for job in jobs.values().filter(|j| j.info.path == path) {
+            match (kind, job.info.kind) {
+                (ScanJobKind::Measure, _) | (_, ScanJobKind::Measure) => {}
+                (ScanJobKind::Full, ScanJobKind::Refresh) => {
+                    job.state.cancel();
+                }
+                _ => {
+                    return Err(AppError::new(
+                        ErrorCode::ScanInProgress,
+                        format!("Scan already in progress for {}", path),
+                    )
+                    .with_path(path))
+                }
+            }
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        if kind == ScanJobKind::Full {
+            self.latest_full.store(id, Ordering::Relaxed);
+        }
+        let state = Arc::new(ScannerState::new());
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        jobs.insert(
+            id,
+            ScanJob {
+                info: ScanJobInfo {
+                    job_id: id,
+                    kind,
+                    path: path.to_string(),
+                    started_at,
+                    cancelled: false,
+                    paused: false,
+                },
+                state: state.clone(),
+            },
+        );
+
+        Ok(ScanJobHandle {
+            manager: self.clone(),
+            id,
+            state,
+        })
+    }
+
+    /// Cancel one job; returns false if it isn't running
+    pub fn cancel(&self, id: JobId) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get_mut(&id) {
+            Some(job) => {
+                job.state.cancel();
+                job.info.cancelled = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel every running job of one kind, returning how many were hit
+    pub fn cancel_kind(&self, kind: ScanJobKind) -> usize {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut cancelled = 0;
+        for job in jobs.values_mut().filter(|j| j.info.kind == kind) {
+            job.state.cancel();
+            job.info.cancelled = true;
+            cancelled += 1;
+        }
+        cancelled
+    }
+
+    /// Pause or resume one job; returns false if it isn't running
+    pub fn set_paused(&self, id: JobId, paused: bool) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get_mut(&id) {
+            Some(job) => {
+                job.pause(paused);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pause or resume every running job of one kind, returning how many were hit
+    pub fn set_paused_kind(&self, kind: ScanJobKind, paused: bool) -> usize {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut count = 0;
+        for job in jobs.values_mut().filter(|j| j.info.kind == kind) {
+            job.pause(paused);
+            count += 1;
+        }
+        count
+    }
+
+    pub fn list(&self) -> Vec<ScanJobInfo> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut list: Vec<ScanJobInfo> = jobs.values().map(|j| j.info.clone()).collect();
+        list.sort_by_key(|j| j.job_id);
+        list
+    }
+
+    /// Whether `id` is still the most recently started full scan
+    pub fn is_latest_full(&self, id: JobId) -> bool {
+        self.latest_full.load(Ordering::Relaxed) == id
+    }
+
+    fn finish(&self, id: JobId) {
+        self.jobs.lock().unwrap().remove(&id);
+    }
+}
+
+/// A running job; it is unregistered when the handle is dropped
+pub struct ScanJobHandle {
+    manager: Arc<ScanManager>,
+    id: JobId,
+    state: Arc<ScannerState>,
+}
+
+impl ScanJobHandle {
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    pub fn state(&self) -> Arc<ScannerState> {
+        self.state.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled()
+    }
+
+    pub fn info(&self) -> Option<ScanJobInfo> {
+        let jobs = self.manager.jobs.lock().unwrap();
+        jobs.get(&self.id).map(|j| j.info.clone())
+    }
+}
+
+impl Drop for ScanJobHandle {
+    fn drop(&mut self) {
+        self.manager.finish(self.id);
+    }
+}
@@ -1,38 +1,183 @@
+mod analysis;
 mod cache;
+mod category;
+mod delete;
+mod error;
+mod index;
+mod jobs;
+mod logging;
+mod platform;
+mod refresh;
+mod safety;
 mod scanner;
-
-use cache::{CacheInfo, CachedScan, DeleteLogEntry, ScanHistoryEntry};
-use scanner::{FileNode, Scanner, ScannerState};
-use std::collections::HashSet;
+mod search;
+mod settings;
+mod watch;
+mod workspace;
+
+use analysis::{AgeAnalysis, OwnerUsage, TopItems, TypeStats};
+use cache::{
+    CacheInfo, CachedScan, DeleteLogEntry, DeleteStats, DeleteStatsQuery, ScanHistoryEntry,
+};
+use category::{CategoryTable, FileCategory};
+use delete::{DeleteOutcome, DeletePlan, DeletePlans};
+use error::{AppError, AppResult, ErrorCode};
+use index::{NodeId, ScanIndex, ROOT_ID};
+use jobs::{JobId, ScanJobInfo, ScanJobKind, ScanManager};
+use logging::LogEntry;
+use scanner::{EstimateSource, FileNode, ScanEstimate, ScanOptions, Scanner};
+use refresh::RefreshPolicy;
+use search::{SearchQuery, SearchResults};
+use settings::{ActiveSettings, Settings, SettingsUpdate};
+use watch::WatchCoverage;
+use workspace::Workspace;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_dialog::DialogExt;
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
 use tokio::time::{sleep, Duration};
+use tracing::{debug, error, info, warn};
+
+/// A change applied to the index directly, without rescanning
+enum StructuralChange {
+    Removed(PathBuf),
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+/// File system events collected between incremental refreshes
+#[derive(Default)]
+struct PendingChanges {
+    /// Paths whose directories need a rescan
+    dirty: HashSet<PathBuf>,
+    /// Removals and renames, in event order
+    structural: Vec<StructuralChange>,
+    /// Rename sources still waiting for their destination
+    rename_from: HashSet<PathBuf>,
+}
+
+impl PendingChanges {
+    fn record(&mut self, event: notify::Event) {
+        match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                let from = event.paths[0].clone();
+                let to = event.paths[1].clone();
+                // The To half of the pair was already marked dirty
+                self.rename_from.remove(&from);
+                self.dirty.remove(&to);
+                self.structural.push(StructuralChange::Renamed { from, to });
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                self.rename_from.extend(event.paths);
+            }
+            EventKind::Remove(_) => {
+                self.structural
+                    .extend(event.paths.into_iter().map(StructuralChange::Removed));
+            }
+            _ => self.dirty.extend(event.paths),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.dirty.is_empty() && self.structural.is_empty() && self.rename_from.is_empty()
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Take everything recorded so far. Unpaired rename sources were moved
+    /// out of the watched tree, so they become removals.
+    fn take(&mut self) -> (HashSet<PathBuf>, Vec<StructuralChange>) {
+        let mut changes = std::mem::take(self);
+        changes
+            .structural
+            .extend(changes.rename_from.into_iter().map(StructuralChange::Removed));
+        (changes.dirty, changes.structural)
+    }
+}
 
 /// Global scanner state
 pub struct AppState {
-    scanner_state: Arc<ScannerState>,
-    current_tree: Arc<Mutex<Option<FileNode>>>,
+    scan_manager: Arc<ScanManager>,
+    /// Live tree; the UI tree is materialized from it on demand
+    current_index: Arc<RwLock<Option<ScanIndex>>>,
+    category_table: Arc<RwLock<CategoryTable>>,
     current_scan_path: Arc<Mutex<Option<String>>>,
-    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
-    dirty_paths: Arc<Mutex<HashSet<PathBuf>>>,
+    watchers: Arc<Mutex<Vec<Box<dyn Watcher + Send>>>>,
+    /// Bumped whenever watching restarts, so stale poller setups are dropped
+    watch_generation: Arc<AtomicU64>,
+    watcher_status: Arc<Mutex<Option<WatcherStatus>>>,
+    pending_changes: Arc<Mutex<PendingChanges>>,
     incremental_scheduled: Arc<AtomicBool>,
-    scan_in_progress: Arc<AtomicBool>,
+    /// When the watcher last recorded an event (drives the debounce)
+    last_event_at: Arc<Mutex<Instant>>,
+    settings: Arc<RwLock<ActiveSettings>>,
+    /// Sequence number of the last `scan-incremental` patch
+    patch_seq: Arc<AtomicU64>,
+    /// Batch deletes waiting for confirmation
+    delete_plans: Arc<DeletePlans>,
+    /// Set while a save of the refreshed index is waiting to run
+    persist_scheduled: Arc<AtomicBool>,
 }
 
 #[derive(Clone, serde::Serialize)]
 struct WatcherStatus {
     active: bool,
     path: String,
+    mode: String, // "native" | "poll" | "hybrid"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poll_interval_secs: Option<u64>,
+    /// Why polling is used instead of native events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// Live vs polled parts of the tree when it exceeds the watch budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage: Option<WatchCoverage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
 }
 
+/// A rescanned subtree, materialized for the UI
+#[derive(Clone, serde::Serialize)]
+struct SubtreePatch {
+    path: String,
+    node: FileNode,
+}
+
+/// New totals for a directory above a replaced subtree
+#[derive(Clone, serde::Serialize)]
+struct AncestorUpdate {
+    path: String,
+    size: u64,
+    file_count: u64,
+    dir_count: u64,
+}
+
+/// Payload of `scan-incremental`. Patches are numbered so the frontend can
+/// detect a gap and call `resync_tree`; `root` is set instead of `subtrees`
+/// when the whole tree was rescanned.
+#[derive(Clone, serde::Serialize)]
+struct IncrementalPatch {
+    seq: u64,
+    root: Option<FileNode>,
+    subtrees: Vec<SubtreePatch>,
+    /// Paths no longer in the tree (moved subtrees appear here and in `subtrees`)
+    removed: Vec<String>,
+    ancestors: Vec<AncestorUpdate>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct TreeSnapshot {
+    seq: u64,
+    root: Option<FileNode>,
+}
+
 #[derive(Clone, serde::Serialize)]
 struct IncrementalStatus {
     phase: String, // "start" | "complete"
@@ -42,94 +187,355 @@ struct IncrementalStatus {
     at: u64,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
+impl AppState {
+    fn new(settings: Settings) -> Self {
         Self {
-            scanner_state: Arc::new(ScannerState::new()),
-            current_tree: Arc::new(Mutex::new(None)),
+            scan_manager: Arc::new(ScanManager::default()),
+            current_index: Arc::new(RwLock::new(None)),
+            category_table: Arc::new(RwLock::new(CategoryTable::with_overrides(
+                cache::load_category_overrides(),
+            ))),
             current_scan_path: Arc::new(Mutex::new(None)),
-            watcher: Arc::new(Mutex::new(None)),
-            dirty_paths: Arc::new(Mutex::new(HashSet::new())),
+            watchers: Arc::new(Mutex::new(Vec::new())),
+            watch_generation: Arc::new(AtomicU64::new(0)),
+            watcher_status: Arc::new(Mutex::new(None)),
+            pending_changes: Arc::new(Mutex::new(PendingChanges::default())),
             incremental_scheduled: Arc::new(AtomicBool::new(false)),
-            scan_in_progress: Arc::new(AtomicBool::new(false)),
+            last_event_at: Arc::new(Mutex::new(Instant::now())),
+            // Loaded settings are checked field by field, so this only
+            // falls back if validation rules changed in between
+            settings: Arc::new(RwLock::new(settings.compile().unwrap_or_default())),
+            patch_seq: Arc::new(AtomicU64::new(0)),
+            delete_plans: Arc::new(DeletePlans::default()),
+            persist_scheduled: Arc::new(AtomicBool::new(false)),
         }
     }
 }
 
-fn start_watching(app: &AppHandle, state: &AppState, scan_path: &str) {
-    let mut watcher_guard = state.watcher.lock().unwrap();
-    // Reset watcher if scan path changed
-    *watcher_guard = None;
-    state.incremental_scheduled.store(false, Ordering::Relaxed);
+/// Start a debounced incremental refresh unless one is already pending or
+/// live updates are paused.
+///
+/// The refresh waits until events have been quiet for the policy's debounce
+/// window, but never longer than its max wait after being scheduled. While
+/// another job holds the path it retries every max wait.
+fn schedule_incremental_refresh(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    if state.settings.read().unwrap().refresh.policy.paused {
+        return;
+    }
+    if state.incremental_scheduled.swap(true, Ordering::Relaxed) {
+        return;
+    }
 
-    let dirty_paths = state.dirty_paths.clone();
-    let incremental_scheduled = state.incremental_scheduled.clone();
-    let scan_path_buf = PathBuf::from(scan_path);
-    let app_handle = app.clone();
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut scheduled_at = Instant::now();
+        loop {
+            let state = app_handle.state::<AppState>();
+            let (debounce, max_wait, paused) = {
+                let active = state.settings.read().unwrap();
+                let policy = &active.refresh.policy;
+                (
+                    Duration::from_millis(policy.debounce_ms),
+                    Duration::from_millis(policy.max_wait_ms),
+                    policy.paused,
+                )
+            };
+            if paused {
+                state.incremental_scheduled.store(false, Ordering::Relaxed);
+                return;
+            }
 
-    let watcher = RecommendedWatcher::new(
-        move |res: Result<notify::Event, notify::Error>| {
-            if let Ok(event) = res {
-                let mut dirty = dirty_paths.lock().unwrap();
-                for path in event.paths {
-                    dirty.insert(path);
-                }
+            let quiet_for = state.last_event_at.lock().unwrap().elapsed();
+            let waited = scheduled_at.elapsed();
+            if quiet_for < debounce && waited < max_wait {
+                sleep((debounce - quiet_for).min(max_wait - waited)).await;
+                continue;
+            }
+
+            // Still scheduled while backing off, so new events don't start
+            // a second task; a full scan clears what it covers when it ends
+            if let Ok(false) = perform_incremental_refresh(app_handle.clone()).await {
+                sleep(max_wait).await;
+                scheduled_at = Instant::now();
+                continue;
+            }
+            break;
+        }
+
+        let state = app_handle.state::<AppState>();
+        state.incremental_scheduled.store(false, Ordering::Relaxed);
 
-                if !incremental_scheduled.swap(true, Ordering::Relaxed) {
-                    let app_handle = app_handle.clone();
-                    let scheduled = incremental_scheduled.clone();
-                    tauri::async_runtime::spawn(async move {
-                        // Debounce multiple file events
-                        sleep(Duration::from_millis(800)).await;
-                        scheduled.store(false, Ordering::Relaxed);
-                        let _ = perform_incremental_refresh(app_handle).await;
-                    });
+        // Events that arrived while the refresh ran need another pass
+        if !state.pending_changes.lock().unwrap().is_empty() {
+            schedule_incremental_refresh(&app_handle);
+        }
+    });
+}
+
+/// How long refreshed results wait before being written to the cache, so a
+/// burst of refreshes is saved once
+const PERSIST_DELAY: Duration = Duration::from_secs(5);
+
+/// Save the live index and its UI tree to the cache after `PERSIST_DELAY`,
+/// unless a save is already pending. The index is snapshotted when the save
+/// runs, so it includes every refresh applied in between.
+fn schedule_persist(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    if state.persist_scheduled.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let persist_scheduled = state.persist_scheduled.clone();
+    let current_index = state.current_index.clone();
+    let current_scan_path = state.current_scan_path.clone();
+    tauri::async_runtime::spawn(async move {
+        sleep(PERSIST_DELAY).await;
+        persist_scheduled.store(false, Ordering::Relaxed);
+
+        let _ = tokio::task::spawn_blocking(move || {
+            let snapshot = {
+                let guard = current_index.read().unwrap();
+                let scan_path = current_scan_path.lock().unwrap().clone();
+                scan_path.zip(guard.clone())
+            };
+            let Some((scan_path, index)) = snapshot else {
+                return;
+            };
+            if let Err(e) = cache::save_index(&scan_path, &index) {
+                error!(error = %e, "failed to save index");
+            }
+            if let Some(root) = scanner::build_ui_tree(&index, ROOT_ID) {
+                let extension_stats = analysis::extension_stats(&index, ROOT_ID);
+                if let Err(e) = cache::save_incremental_update(&scan_path, &root, &extension_stats)
+                {
+                    error!(error = %e, "failed to save incremental update");
                 }
             }
-        },
-        Config::default(),
-    );
+        })
+        .await;
+    });
+}
+
+/// Build the notify callback that records events (minus ignored paths) and
+/// schedules a debounced incremental refresh
+fn watch_event_handler(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+) -> impl FnMut(notify::Result<notify::Event>) + Send + 'static {
+    let pending_changes = state.pending_changes.clone();
+    let last_event_at = state.last_event_at.clone();
+    let settings = state.settings.clone();
+    let root = root.to_path_buf();
+    let app_handle = app.clone();
 
-    match watcher {
-        Ok(mut watcher) => {
-            if watcher
-                .watch(&scan_path_buf, RecursiveMode::Recursive)
-                .is_ok()
+    move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
             {
-                *watcher_guard = Some(watcher);
-                let _ = app.emit(
-                    "watcher-status",
-                    WatcherStatus {
-                        active: true,
-                        path: scan_path.to_string(),
-                        error: None,
-                    },
-                );
-            } else {
-                let _ = app.emit(
-                    "watcher-status",
-                    WatcherStatus {
-                        active: false,
-                        path: scan_path.to_string(),
-                        error: Some("Failed to watch path".to_string()),
-                    },
-                );
+                let active = settings.read().unwrap();
+                let dropped = |p: &PathBuf| {
+                    active.refresh.is_ignored(&root, p) || active.excludes.matches(&root, p)
+                };
+                if event.paths.iter().all(dropped) {
+                    return;
+                }
             }
+            pending_changes.lock().unwrap().record(event);
+            *last_event_at.lock().unwrap() = Instant::now();
+            schedule_incremental_refresh(&app_handle);
         }
-        Err(e) => {
-            eprintln!("[Watch] Failed to init watcher: {}", e);
-            let _ = app.emit(
-                "watcher-status",
-                WatcherStatus {
-                    active: false,
-                    path: scan_path.to_string(),
-                    error: Some(e.to_string()),
-                },
-            );
-        }
     }
 }
 
+/// Create one watcher over `targets`: native events, or polling every
+/// `poll_interval` when one is given. `root` is the scan root, used to match
+/// ignore patterns.
+fn create_watcher(
+    app: &AppHandle,
+    state: &AppState,
+    root: &Path,
+    targets: &[(PathBuf, RecursiveMode)],
+    poll_interval: Option<Duration>,
+) -> notify::Result<Box<dyn Watcher + Send>> {
+    let handler = watch_event_handler(app, state, root);
+    let mut watcher: Box<dyn Watcher + Send> = match poll_interval {
+        None => Box::new(RecommendedWatcher::new(handler, Config::default())?),
+        Some(interval) => Box::new(PollWatcher::new(
+            handler,
+            Config::default().with_poll_interval(interval),
+        )?),
+    };
+    for (path, mode) in targets {
+        watcher.watch(path, *mode)?;
+    }
+    Ok(watcher)
+}
+
+/// Remember and broadcast the watcher status
+fn set_watcher_status(app: &AppHandle, state: &AppState, status: WatcherStatus) {
+    *state.watcher_status.lock().unwrap() = Some(status.clone());
+    let _ = app.emit("watcher-status", status);
+}
+
+fn start_watching(app: &AppHandle, state: &AppState, scan_path: &str) {
+    let mut watchers = state.watchers.lock().unwrap();
+    // Reset watchers if scan path changed
+    watchers.clear();
+    let generation = state.watch_generation.fetch_add(1, Ordering::Relaxed) + 1;
+    state.incremental_scheduled.store(false, Ordering::Relaxed);
+
+    if workspace::workspace_name(scan_path).is_some() {
+        drop(watchers);
+        set_watcher_status(
+            app,
+            state,
+            WatcherStatus {
+                active: false,
+                path: scan_path.to_string(),
+                mode: "native".to_string(),
+                poll_interval_secs: None,
+                reason: Some("Live updates are not available for workspaces".to_string()),
+                coverage: None,
+                error: None,
+            },
+        );
+        return;
+    }
+
+    let root = PathBuf::from(scan_path);
+    let status = |mode: &str, reason: Option<String>, coverage: Option<WatchCoverage>| WatcherStatus {
+        active: true,
+        path: scan_path.to_string(),
+        mode: mode.to_string(),
+        poll_interval_secs: None,
+        reason,
+        coverage,
+        error: None,
+    };
+
+    // Network shares and FUSE mounts never deliver native events for changes
+    // made elsewhere, so they go straight to polling
+    let fallback_reason = if let Some(fs_type) = platform::remote_filesystem(&root) {
+        format!("{} filesystem", fs_type)
+    } else {
+        // More directories than the inotify budget: watch the largest
+        // subtrees natively and poll the rest
+        let plan = platform::inotify_max_user_watches().and_then(|max| {
+            let guard = state.current_index.read().unwrap();
+            watch::plan_watches(guard.as_ref()?, watch::usable_budget(max))
+        });
+
+        let native_targets: Vec<(PathBuf, RecursiveMode)> = match &plan {
+            Some(plan) => plan
+                .native
+                .iter()
+                .map(|p| (p.clone(), RecursiveMode::Recursive))
+                .collect(),
+            None => vec![(root.clone(), RecursiveMode::Recursive)],
+        };
+
+        match create_watcher(app, state, &root, &native_targets, None) {
+            Ok(watcher) => {
+                watchers.push(watcher);
+                drop(watchers);
+                match plan {
+                    None => set_watcher_status(app, state, status("native", None, None)),
+                    Some(plan) => {
+                        let reason = format!(
+                            "{} directories exceed the inotify budget of {}",
+                            plan.total_dirs, plan.budget
+                        );
+                        info!(
+                            native_dirs = plan.native_dirs,
+                            "{}; rest of the tree is polled",
+                            reason
+                        );
+                        let poll_targets = plan
+                            .poll_recursive
+                            .iter()
+                            .map(|p| (p.clone(), RecursiveMode::Recursive))
+                            .chain(
+                                plan.poll_shallow
+                                    .iter()
+                                    .map(|p| (p.clone(), RecursiveMode::NonRecursive)),
+                            )
+                            .collect();
+                        spawn_poll_watcher(
+                            app,
+                            state,
+                            generation,
+                            root,
+                            poll_targets,
+                            status("hybrid", Some(reason), Some(plan.coverage())),
+                        );
+                    }
+                }
+                return;
+            }
+            Err(e) => {
+                warn!(error = %e, "native watcher failed");
+                format!("native watcher failed: {}", e)
+            }
+        }
+    };
+    drop(watchers);
+
+    let targets = vec![(root.clone(), RecursiveMode::Recursive)];
+    let status = status("poll", Some(fallback_reason), None);
+    spawn_poll_watcher(app, state, generation, root, targets, status);
+}
+
+/// Set up a poll watcher off the async runtime (it walks its targets up
+/// front) and publish `status` once it is running
+fn spawn_poll_watcher(
+    app: &AppHandle,
+    state: &AppState,
+    generation: u64,
+    root: PathBuf,
+    targets: Vec<(PathBuf, RecursiveMode)>,
+    mut status: WatcherStatus,
+) {
+    let interval_secs = state.settings.read().unwrap().settings.watch_poll_interval_secs;
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        info!(
+            paths = targets.len(),
+            root = %root.display(),
+            interval_secs,
+            "polling for changes"
+        );
+        let state = app.state::<AppState>();
+        let watcher = create_watcher(
+            &app,
+            &state,
+            &root,
+            &targets,
+            Some(Duration::from_secs(interval_secs)),
+        );
+
+        // Watching may have been restarted while the poller was set up
+        let mut watchers = state.watchers.lock().unwrap();
+        if state.watch_generation.load(Ordering::Relaxed) != generation {
+            return;
+        }
+
+        match watcher {
+            Ok(watcher) => {
+                watchers.push(watcher);
+                status.poll_interval_secs = Some(interval_secs);
+            }
+            Err(e) => {
+                error!(error = %e, "failed to init poll watcher");
+                status.active = !watchers.is_empty();
+                status.error = Some(e.to_string());
+            }
+        }
+        drop(watchers);
+        set_watcher_status(&app, &state, status);
+    });
+}
+
 fn coalesce_dirty_dirs(dirty_paths: HashSet<PathBuf>, scan_root: &PathBuf) -> Vec<PathBuf> {
     let mut dirs: Vec<PathBuf> = Vec::new();
 
@@ -169,110 +575,132 @@ fn coalesce_dirty_dirs(dirty_paths: HashSet<PathBuf>, scan_root: &PathBuf) -> Ve
     filtered
 }
 
-fn recompute_dir_stats(node: &FileNode) -> (u64, u64, u64) {
-    let mut size = 0u64;
-    let mut files = 0u64;
-    let mut dirs = 0u64;
-
-    for child in &node.children {
-        size += child.size;
-        if child.is_dir {
-            files += child.file_count;
-            dirs += 1 + child.dir_count;
-        } else {
-            files += 1;
+/// Apply removals and renames to the index in event order, returning the
+/// removed paths and the new locations of moved subtrees. Anything that
+/// can't be resolved from the index alone is marked dirty instead.
+fn apply_structural_changes(
+    index: &mut ScanIndex,
+    changes: Vec<StructuralChange>,
+    dirty: &mut HashSet<PathBuf>,
+) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let mut removed = Vec::new();
+    let mut moved = Vec::new();
+
+    for change in changes {
+        match change {
+            StructuralChange::Removed(path) => {
+                if fs::symlink_metadata(&path).is_ok() {
+                    // Recreated since the event fired
+                    dirty.insert(path);
+                } else if index.remove(&path) {
+                    removed.push(path);
+                }
+            }
+            StructuralChange::Renamed { from, to } => {
+                if index.move_node(&from, &to) {
+                    debug!(from = %from.display(), to = %to.display(), "moved");
+                    removed.push(from);
+                    moved.push(to);
+                } else {
+                    if index.remove(&from) {
+                        removed.push(from);
+                    }
+                    dirty.insert(to);
+                }
+            }
         }
     }
 
-    (size, files, dirs)
+    (removed, moved)
 }
 
-fn node_exists(root: &FileNode, target_path: &str) -> bool {
-    if root.path == target_path {
-        return true;
-    }
-    if !root.is_dir {
-        return false;
-    }
-    for child in &root.children {
-        if node_exists(child, target_path) {
-            return true;
-        }
-    }
-    false
-}
-
-fn replace_subtree(mut root: FileNode, target_path: &str, new_subtree: &FileNode) -> FileNode {
-    if root.path == target_path {
-        return new_subtree.clone();
-    }
-
-    if !root.is_dir {
-        return root;
-    }
-
-    let mut changed = false;
-    let mut children = Vec::with_capacity(root.children.len());
-
-    for child in root.children.iter().cloned() {
-        if child.path == target_path {
-            children.push(new_subtree.clone());
-            changed = true;
-            continue;
+/// Collect the replaced subtrees, the removed paths, and the new totals of
+/// every directory above them (each ancestor listed once, deepest first)
+fn build_incremental_patch(
+    index: &ScanIndex,
+    seq: u64,
+    replaced: &[PathBuf],
+    removed: &[PathBuf],
+) -> IncrementalPatch {
+    let mut subtrees = Vec::with_capacity(replaced.len());
+    let mut ancestors = Vec::new();
+    let mut seen = HashSet::new();
+
+    let mut push_chain = |from: Option<NodeId>, ancestors: &mut Vec<AncestorUpdate>| {
+        let mut current = from;
+        while let Some(id) = current {
+            if !seen.insert(id) {
+                break;
+            }
+            let Some(node) = index.get(id) else { break };
+            ancestors.push(AncestorUpdate {
+                path: index.path_of(id).to_string_lossy().to_string(),
+                size: node.size,
+                file_count: node.file_count,
+                dir_count: node.dir_count,
+            });
+            current = node.parent;
         }
+    };
 
-        if target_path.starts_with(&format!("{}/", child.path)) {
-            let updated = replace_subtree(child, target_path, new_subtree);
-            children.push(updated);
-            changed = true;
-        } else {
-            children.push(child);
+    for dir in replaced {
+        let Some(id) = index.find(dir) else { continue };
+        if let Some(node) = scanner::build_ui_tree(index, id) {
+            subtrees.push(SubtreePatch {
+                path: dir.to_string_lossy().to_string(),
+                node,
+            });
         }
+        push_chain(index.get(id).and_then(|n| n.parent), &mut ancestors);
     }
 
-    if !changed {
-        return root;
+    for path in removed {
+        // Nearest directory that is still indexed
+        let nearest = path.ancestors().skip(1).find_map(|p| index.find(p));
+        push_chain(nearest, &mut ancestors);
     }
 
-    root.children = children;
-    let (size, files, dirs) = recompute_dir_stats(&root);
-    root.size = size;
-    root.file_count = files;
-    root.dir_count = dirs;
-    root
+    IncrementalPatch {
+        seq,
+        root: None,
+        subtrees,
+        removed: removed.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        ancestors,
+    }
 }
 
-async fn perform_incremental_refresh(app_handle: AppHandle) -> Result<(), String> {
+/// Apply pending watcher changes to the live index. Returns false, leaving
+/// the changes pending, when a full scan or another refresh holds the path.
+async fn perform_incremental_refresh(app_handle: AppHandle) -> AppResult<bool> {
     let state = app_handle.state::<AppState>();
-    if state.scan_in_progress.swap(true, Ordering::Relaxed) {
-        return Ok(());
-    }
 
     let scan_path = {
         let path_guard = state.current_scan_path.lock().unwrap();
         path_guard.clone()
     };
 
+    // Workspaces aren't watched; they are refreshed by rescanning
     let scan_path = match scan_path {
-        Some(p) => p,
-        None => {
-            state.scan_in_progress.store(false, Ordering::Relaxed);
-            return Ok(());
-        }
+        Some(p) if workspace::workspace_name(&p).is_none() => p,
+        _ => return Ok(true),
+    };
+
+    // Refused while a full scan or another refresh of this path is running;
+    // the job is unregistered when `job` drops
+    let job = match state.scan_manager.start(ScanJobKind::Refresh, &scan_path) {
+        Ok(job) => job,
+        Err(_) => return Ok(false),
     };
 
     let root_path = PathBuf::from(&scan_path);
 
-    let dirty_paths = {
-        let mut guard = state.dirty_paths.lock().unwrap();
-        std::mem::take(&mut *guard)
+    let changes = {
+        let mut guard = state.pending_changes.lock().unwrap();
+        (!guard.is_empty()).then(|| guard.take())
     };
 
-    if dirty_paths.is_empty() {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| e.to_string())?
-            .as_secs();
+    let Some((mut dirty_paths, structural)) = changes else {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         let _ = app_handle.emit(
             "incremental-status",
             IncrementalStatus {
@@ -283,18 +711,38 @@ async fn perform_incremental_refresh(app_handle: AppHandle) -> Result<(), String
                 at: now,
             },
         );
-        state.scan_in_progress.store(false, Ordering::Relaxed);
-        return Ok(());
-    }
+        return Ok(true);
+    };
+
+    // Removals and renames are applied straight to the index; without one,
+    // everything falls through to a rescan
+    let structural_count = structural.len();
+    let (removed, moved) = {
+        let mut guard = state.current_index.write().unwrap();
+        match guard.as_mut() {
+            Some(index) => apply_structural_changes(index, structural, &mut dirty_paths),
+            None => {
+                for change in structural {
+                    match change {
+                        StructuralChange::Removed(path) => dirty_paths.insert(path),
+                        StructuralChange::Renamed { from, to } => {
+                            dirty_paths.insert(from);
+                            dirty_paths.insert(to)
+                        }
+                    };
+                }
+                (Vec::new(), Vec::new())
+            }
+        }
+    };
 
     let dirty_dirs = coalesce_dirty_dirs(dirty_paths, &root_path);
-    let dirty_count = dirty_dirs.len();
-    let full_rescan = dirty_dirs.len() > 40 || dirty_dirs.iter().any(|p| p == &root_path);
+    let dirty_count = dirty_dirs.len() + structural_count;
+    let full_rescan_threshold = state.settings.read().unwrap().refresh.policy.full_rescan_threshold;
+    let full_rescan =
+        dirty_dirs.len() > full_rescan_threshold || dirty_dirs.iter().any(|p| p == &root_path);
 
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| e.to_string())?
-        .as_secs();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
     let _ = app_handle.emit(
         "incremental-status",
         IncrementalStatus {
@@ -306,78 +754,107 @@ async fn perform_incremental_refresh(app_handle: AppHandle) -> Result<(), String
         },
     );
 
-    let mut updated_root = {
-        let tree_guard = state.current_tree.lock().unwrap();
-        tree_guard.clone()
+    // Map dirty dirs onto the index; a new dir is scanned on its own and
+    // inserted under its nearest indexed ancestor
+    let effective_dirs: Option<Vec<PathBuf>> = {
+        let guard = state.current_index.read().unwrap();
+        guard.as_ref().filter(|_| !full_rescan).map(|index| {
+            let mut dirs: Vec<PathBuf> = dirty_dirs
+                .iter()
+                .filter_map(|dir| {
+                    let target = index.rescan_target(dir);
+                    if target.is_none() {
+                        warn!(path = %dir.display(), "no indexed ancestor; change skipped");
+                    }
+                    target
+                })
+                .collect();
+            // Sorted by component, so descendants follow the dir covering them
+            dirs.sort();
+            dirs.dedup_by(|dir, kept| dir.starts_with(kept));
+            dirs
+        })
     };
 
-    if updated_root.is_none() || full_rescan {
-        let scanner = Scanner::new(state.scanner_state.clone());
-        let result = tokio::task::spawn_blocking(move || scanner.scan(&root_path, None))
-            .await
-            .map_err(|e| e.to_string())?;
-        if let Some(root) = result {
-            updated_root = Some(root);
-        }
-    } else if let Some(root) = updated_root.take() {
-        let mut next_root = root;
-        let mut effective_dirs: Vec<PathBuf> = Vec::new();
-        for dir in dirty_dirs.iter() {
-            let target = dir.to_string_lossy().to_string();
-            if node_exists(&next_root, &target) {
-                effective_dirs.push(dir.clone());
-            } else if let Some(parent) = dir.parent() {
-                effective_dirs.push(parent.to_path_buf());
-            } else {
-                effective_dirs.push(root_path.clone());
-            }
-        }
-
-        effective_dirs.sort();
-        effective_dirs.dedup();
-
-        if effective_dirs.iter().any(|p| p == &root_path) {
-            let scanner = Scanner::new(state.scanner_state.clone());
-            let result = tokio::task::spawn_blocking(move || scanner.scan(&root_path, None))
-                .await
-                .map_err(|e| e.to_string())?;
-            if let Some(root) = result {
-                updated_root = Some(root);
-            }
-        } else {
-            let scanner_state = state.scanner_state.clone();
-            for dir in effective_dirs {
-                if dir == root_path {
-                    continue;
+    let (scan_options, excludes) = {
+        let active = state.settings.read().unwrap();
+        (active.refresh.policy.scan_options.clone(), active.excludes.clone())
+    };
+    let mut updated = !removed.is_empty() || !moved.is_empty();
+    let mut full_replace = false;
+    let mut replaced: Vec<PathBuf> = moved;
+    match effective_dirs {
+        Some(dirs) if !dirs.iter().any(|p| p == &root_path) => {
+            for dir in dirs {
+                if job.is_cancelled() {
+                    break;
                 }
                 let dir_clone = dir.clone();
-                let scanner_state = scanner_state.clone();
-                if let Ok(Some(subtree)) = tokio::task::spawn_blocking(move || {
-                    let scanner = Scanner::new(scanner_state);
-                    scanner.scan(&dir_clone, None)
-                })
-                .await
-                .map_err(|e| e.to_string())
+                let scanner = Scanner::new(job.state())
+                    .with_job_id(job.id())
+                    .with_options(scan_options.clone())
+                    .with_excludes(excludes.clone(), Some(root_path.clone()));
+                if let Ok(Some(subtree)) =
+                    tokio::task::spawn_blocking(move || scanner.scan(&dir_clone, None)).await
                 {
-                    let target = dir.to_string_lossy().to_string();
-                    next_root = replace_subtree(next_root, &target, &subtree);
+                    // Splice in place; only the ancestors of `dir` are touched
+                    let mut guard = state.current_index.write().unwrap();
+                    if let Some(index) = guard.as_mut() {
+                        if index.replace_subtree(&dir, subtree.index) {
+                            replaced.push(dir);
+                            updated = true;
+                        }
+                    }
                 }
             }
-            updated_root = Some(next_root);
+        }
+        _ => {
+            let scanner = Scanner::new(job.state())
+                .with_job_id(job.id())
+                .with_options(scan_options)
+                .with_excludes(excludes, None);
+            let result =
+                tokio::task::spawn_blocking(move || scanner.scan(&root_path, None)).await?;
+            if let Some(result) = result {
+                *state.current_index.write().unwrap() = Some(result.index);
+                updated = true;
+                full_replace = true;
+            }
         }
     }
 
-    let result = if let Some(root) = updated_root {
-        {
-            let mut tree = state.current_tree.lock().unwrap();
-            *tree = Some(root.clone());
-        }
-        cache::save_incremental_update(&scan_path, &root)?;
-        let _ = app_handle.emit("scan-incremental", &root);
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| e.to_string())?
-            .as_secs();
+    // Materializing the patch walks the changed subtrees (or the whole tree
+    // after a full rescan), so it runs off the async runtime; saving to the
+    // cache is left to `schedule_persist`
+    let patch = if updated {
+        let current_index = state.current_index.clone();
+        let patch_seq = state.patch_seq.clone();
+        tokio::task::spawn_blocking(move || {
+            let guard = current_index.read().unwrap();
+            guard.as_ref().map(|index| {
+                let seq = patch_seq.fetch_add(1, Ordering::Relaxed) + 1;
+                if full_replace {
+                    IncrementalPatch {
+                        seq,
+                        root: scanner::build_ui_tree(index, ROOT_ID),
+                        subtrees: Vec::new(),
+                        removed: Vec::new(),
+                        ancestors: Vec::new(),
+                    }
+                } else {
+                    build_incremental_patch(index, seq, &replaced, &removed)
+                }
+            })
+        })
+        .await?
+    } else {
+        None
+    };
+
+    if let Some(patch) = patch {
+        let _ = app_handle.emit("scan-incremental", &patch);
+        schedule_persist(&app_handle);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         let _ = app_handle.emit(
             "incremental-status",
             IncrementalStatus {
@@ -388,12 +865,9 @@ async fn perform_incremental_refresh(app_handle: AppHandle) -> Result<(), String
                 at: now,
             },
         );
-        Ok(())
+        Ok(true)
     } else {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| e.to_string())?
-            .as_secs();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         let _ = app_handle.emit(
             "incremental-status",
             IncrementalStatus {
@@ -404,11 +878,27 @@ async fn perform_incremental_refresh(app_handle: AppHandle) -> Result<(), String
                 at: now,
             },
         );
-        Ok(())
-    };
+        Ok(true)
+    }
+}
 
-    state.scan_in_progress.store(false, Ordering::Relaxed);
-    result
+/// Expected entry count for a scan of `path`: the previous scan's count if
+/// there is one, else the volume's used inodes when scanning a mount point
+fn estimate_scan(path: &str) -> Option<ScanEstimate> {
+    if let Some(entries) = cache::load_entry_count(path) {
+        return Some(ScanEstimate {
+            entries,
+            source: EstimateSource::PreviousScan,
+        });
+    }
+    let path = Path::new(path);
+    if !platform::is_mount_point(path) {
+        return None;
+    }
+    platform::used_inodes(path).map(|entries| ScanEstimate {
+        entries,
+        source: EstimateSource::Inodes,
+    })
 }
 
 /// Scan a specific directory (with optional caching)
@@ -418,86 +908,125 @@ async fn scan_directory(
     state: State<'_, AppState>,
     path: String,
     use_cache: Option<bool>,
-) -> Result<Option<FileNode>, String> {
+    options: Option<ScanOptions>,
+) -> AppResult<Option<FileNode>> {
     let path_buf = PathBuf::from(&path);
 
-    if !path_buf.exists() {
-        return Err(format!("Path does not exist: {}", path));
-    }
-
-    if !path_buf.is_dir() {
-        return Err(format!("Path is not a directory: {}", path));
-    }
+    // A workspace key scans the workspace's roots into one combined tree
+    let workspace = match workspace::workspace_name(&path) {
+        Some(name) => {
+            let workspace = cache::load_workspace(name)
+                .ok_or_else(|| AppError::new(ErrorCode::NotFound, format!("Unknown workspace: {}", name)))?;
+            let roots = workspace.validate()?;
+            Some((workspace.name, roots))
+        }
+        None => {
+            if !path_buf.exists() {
+                return Err(AppError::not_found(&path));
+            }
+            if !path_buf.is_dir() {
+                return Err(AppError::new(
+                    ErrorCode::NotADirectory,
+                    format!("Path is not a directory: {}", path),
+                )
+                .with_path(&path));
+            }
+            None
+        }
+    };
 
-    if state.scan_in_progress.swap(true, Ordering::Relaxed) {
-        return Err("Scan already in progress".to_string());
-    }
+    let (options, excludes) = {
+        let active = state.settings.read().unwrap();
+        let options = options.unwrap_or_else(|| active.settings.scan_options.clone());
+        (options, active.excludes.clone())
+    };
+    options.validate()?;
 
     // Try to load from cache first if use_cache is true (default)
     let should_use_cache = use_cache.unwrap_or(true);
     if should_use_cache {
-        if let Ok(cached) = cache::load_from_cache(&path) {
-            println!("[Scan] Using cached result for {}", path);
+        // A stale or damaged cache is reported rather than silently rescanned,
+        // so the user can decide whether a full scan is worth it now
+        let cached = match cache::load_from_cache(&path) {
+            Ok(cached) => Some(cached),
+            Err(e) if e.is_stale_cache() => return Err(e),
+            Err(_) => None,
+        };
+        if let Some(cached) = cached {
+            info!(path = %path, "using cached result");
             // Emit cache-loaded event
             let _ = app_handle.emit("scan-from-cache", &cached);
-            {
-                let mut tree = state.current_tree.lock().unwrap();
-                *tree = Some(cached.root.clone());
-            }
+            let index = cache::load_index(&path)
+                .unwrap_or_else(|_| ScanIndex::from_file_node(&cached.root));
+            *state.current_index.write().unwrap() = Some(index);
+            state.patch_seq.store(0, Ordering::Relaxed);
             {
                 let mut scan_path = state.current_scan_path.lock().unwrap();
                 *scan_path = Some(path.clone());
             }
-            {
-                let mut dirty = state.dirty_paths.lock().unwrap();
-                dirty.clear();
-            }
-            state.scan_in_progress.store(false, Ordering::Relaxed);
+            state.pending_changes.lock().unwrap().clear();
             start_watching(&app_handle, &state, &path);
             return Ok(Some(cached.root));
         }
     }
 
-    let scanner = Scanner::new(state.scanner_state.clone());
+    let job = state.scan_manager.start(ScanJobKind::Full, &path)?;
+    if let Some(info) = job.info() {
+        let _ = app_handle.emit("scan-started", info);
+    }
+
+    let scanner = Scanner::new(job.state())
+        .with_job_id(job.id())
+        .with_options(options)
+        .with_excludes(excludes, None)
+        .with_estimate(estimate_scan(&path));
     let path_for_cache = path.clone();
     let app_for_cache = app_handle.clone();
 
     // Run scanning in a blocking task to not block the async runtime
     let app_for_scan = app_handle.clone();
-    let result = tokio::task::spawn_blocking(move || scanner.scan(&path_buf, Some(&app_for_scan)))
-        .await
-        .map_err(|e| e.to_string());
-
-    // Save to cache after successful scan
-    if let Ok(Some(ref root)) = result {
-        {
-            let mut tree = state.current_tree.lock().unwrap();
-            *tree = Some(root.clone());
-        }
-        {
-            let mut scan_path = state.current_scan_path.lock().unwrap();
-            *scan_path = Some(path.clone());
-        }
-        {
-            let mut dirty = state.dirty_paths.lock().unwrap();
-            dirty.clear();
+    let result = tokio::task::spawn_blocking(move || match workspace {
+        Some((name, roots)) => scanner.scan_workspace(&name, &roots, Some(&app_for_scan)),
+        None => scanner.scan(&path_buf, Some(&app_for_scan)),
+    })
+    .await
+    .map_err(AppError::from);
+
+    // Save to cache after successful scan; only the most recently started
+    // scan becomes the live tree
+    if let Ok(Some(ref scan)) = result {
+        let is_current = state.scan_manager.is_latest_full(job.id());
+        if is_current {
+            *state.current_index.write().unwrap() = Some(scan.index.clone());
+            state.patch_seq.store(0, Ordering::Relaxed);
+            {
+                let mut scan_path = state.current_scan_path.lock().unwrap();
+                *scan_path = Some(path.clone());
+            }
+            state.pending_changes.lock().unwrap().clear();
         }
-        let root_clone = root.clone();
+        let root_clone = scan.root.clone();
+        let index_clone = scan.index.clone();
+        let stats_clone = scan.extension_stats.clone();
         tokio::task::spawn_blocking(move || {
-            match cache::save_to_cache(&path_for_cache, &root_clone) {
+            match cache::save_to_cache(&path_for_cache, &root_clone, &stats_clone) {
                 Ok(cache_path) => {
                     let _ = app_for_cache.emit("cache-saved", cache_path.to_string_lossy().to_string());
                 }
                 Err(e) => {
-                    eprintln!("[Cache] Failed to save: {}", e);
+                    error!(error = %e, "failed to save cache");
                 }
             }
+            if let Err(e) = cache::save_index(&path_for_cache, &index_clone) {
+                error!(error = %e, "failed to save index");
+            }
         });
-        start_watching(&app_handle, &state, &path);
+        if is_current {
+            start_watching(&app_handle, &state, &path);
+        }
     }
 
-    state.scan_in_progress.store(false, Ordering::Relaxed);
-    result
+    result.map(|scan| scan.map(|scan| scan.root))
 }
 
 /// Check if cache exists for a path
@@ -508,21 +1037,19 @@ fn check_cache(path: String) -> Option<CacheInfo> {
 
 /// Load scan results from cache only (don't scan)
 #[tauri::command]
-async fn load_from_cache(path: String) -> Result<CachedScan, String> {
-    tokio::task::spawn_blocking(move || cache::load_from_cache(&path))
-        .await
-        .map_err(|e| e.to_string())?
+async fn load_from_cache(path: String) -> AppResult<CachedScan> {
+    tokio::task::spawn_blocking(move || cache::load_from_cache(&path)).await?
 }
 
 /// Delete cache for a specific path
 #[tauri::command]
-fn delete_cache(path: String) -> Result<(), String> {
+fn delete_cache(path: String) -> AppResult<()> {
     cache::delete_cache(&path)
 }
 
 /// Clear all caches
 #[tauri::command]
-fn clear_all_caches() -> Result<usize, String> {
+fn clear_all_caches() -> AppResult<usize> {
     cache::clear_all_caches()
 }
 
@@ -538,20 +1065,310 @@ fn get_delete_log(scan_path: String, limit: Option<u32>) -> Vec<DeleteLogEntry>
     cache::get_delete_log(&scan_path, limit.unwrap_or(20) as usize)
 }
 
+/// Space reclaimed by logged deletes, per period, scan path, category and
+/// extension
+#[tauri::command]
+fn get_delete_stats(query: DeleteStatsQuery) -> AppResult<DeleteStats> {
+    cache::delete_stats(&query)
+}
+
+/// Run a read-only query against the live index, or the cached index when
+/// `path` lies outside the current scan
+fn with_index<T>(
+    current_index: &RwLock<Option<ScanIndex>>,
+    path: Option<&str>,
+    query: impl FnOnce(&ScanIndex) -> AppResult<T>,
+) -> AppResult<T> {
+    {
+        let guard = current_index.read().unwrap();
+        if let Some(index) = guard.as_ref() {
+            let in_current = path.is_none_or(|p| index.contains_path(Path::new(p)));
+            if in_current {
+                return query(index);
+            }
+        }
+    }
+
+    let scan_path = path.ok_or_else(|| AppError::new(ErrorCode::NoScan, "No scan loaded"))?;
+    let index = cache::load_index(scan_path)
+        .map_err(|e| e.with_context("Loading the cached index of a previous scan"))?;
+    query(&index)
+}
+
+/// Search the full scan index (the live one, or a cached one for other paths)
+///
+/// Results come back a page at a time (`offset`/`limit`) rather than being
+/// streamed as events: they are ordered by size, so no batch is final until
+/// every match has been collected, and the matching pass is a fast walk of
+/// the in-memory index. Paging keeps each response small; callers ask for
+/// further pages only when they need them.
+#[tauri::command]
+async fn search(state: State<'_, AppState>, query: SearchQuery) -> AppResult<SearchResults> {
+    let current_index = state.current_index.clone();
+    let categories = state.category_table.read().unwrap().clone();
+    tokio::task::spawn_blocking(move || {
+        with_index(&current_index, query.path.as_deref(), |index| {
+            search::search_index(index, &query, &categories)
+        })
+    })
+    .await?
+}
+
+/// Largest files and leaf-ish directories under a path (defaults to the scan root)
+#[tauri::command]
+async fn get_top_items(
+    state: State<'_, AppState>,
+    path: Option<String>,
+    limit: Option<u32>,
+) -> AppResult<TopItems> {
+    let current_index = state.current_index.clone();
+    let limit = limit.unwrap_or(100) as usize;
+    tokio::task::spawn_blocking(move || {
+        with_index(&current_index, path.as_deref(), |index| {
+            let start = index.resolve(path.as_deref())?;
+            Ok(analysis::top_items(index, start, limit))
+        })
+    })
+    .await?
+}
+
+/// Per-extension and per-category totals under a path (defaults to the scan root)
+#[tauri::command]
+async fn get_type_stats(state: State<'_, AppState>, path: Option<String>) -> AppResult<TypeStats> {
+    let current_index = state.current_index.clone();
+    let categories = state.category_table.read().unwrap().clone();
+    tokio::task::spawn_blocking(move || {
+        with_index(&current_index, path.as_deref(), |index| {
+            let start = index.resolve(path.as_deref())?;
+            let extensions = analysis::extension_stats(index, start);
+            Ok(analysis::type_stats(&extensions, &categories))
+        })
+    })
+    .await?
+}
+
+/// Age histogram plus the largest directories untouched for `stale_days`
+#[tauri::command]
+async fn get_age_analysis(
+    state: State<'_, AppState>,
+    path: Option<String>,
+    stale_days: Option<u32>,
+    limit: Option<u32>,
+) -> AppResult<AgeAnalysis> {
+    let current_index = state.current_index.clone();
+    let stale_after_secs = stale_days.unwrap_or(365) as u64 * 24 * 60 * 60;
+    let limit = limit.unwrap_or(50) as usize;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    tokio::task::spawn_blocking(move || {
+        with_index(&current_index, path.as_deref(), |index| {
+            let start = index.resolve(path.as_deref())?;
+            Ok(analysis::age_analysis(index, start, now, stale_after_secs, limit))
+        })
+    })
+    .await?
+}
+
+/// Disk usage per owning user under a path (defaults to the scan root)
+#[tauri::command]
+async fn get_owner_usage(
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> AppResult<Vec<OwnerUsage>> {
+    let current_index = state.current_index.clone();
+    tokio::task::spawn_blocking(move || {
+        with_index(&current_index, path.as_deref(), |index| {
+            let start = index.resolve(path.as_deref())?;
+            Ok(analysis::owner_usage(index, start))
+        })
+    })
+    .await?
+}
+
+/// Get user extension -> category overrides
+#[tauri::command]
+fn get_category_overrides(state: State<'_, AppState>) -> HashMap<String, FileCategory> {
+    state.category_table.read().unwrap().overrides().clone()
+}
+
+/// Override the category of an extension (None restores the built-in mapping)
+#[tauri::command]
+fn set_category_override(
+    state: State<'_, AppState>,
+    extension: String,
+    category: Option<FileCategory>,
+) -> AppResult<()> {
+    let extension = category::normalize_extension(&extension);
+    if extension.is_empty() {
+        return Err(AppError::invalid("Extension must not be empty"));
+    }
+
+    cache::set_category_override(&extension, category)?;
+
+    let mut table = state.category_table.write().unwrap();
+    let mut overrides = table.overrides().clone();
+    match category {
+        Some(category) => overrides.insert(extension, category),
+        None => overrides.remove(&extension),
+    };
+    *table = CategoryTable::with_overrides(overrides);
+    Ok(())
+}
+
+/// Current UI tree and patch sequence, for a frontend that missed a
+/// `scan-incremental` patch
+#[tauri::command]
+async fn resync_tree(state: State<'_, AppState>) -> AppResult<TreeSnapshot> {
+    let current_index = state.current_index.clone();
+    let patch_seq = state.patch_seq.clone();
+    tokio::task::spawn_blocking(move || {
+        let guard = current_index.read().unwrap();
+        TreeSnapshot {
+            seq: patch_seq.load(Ordering::Relaxed),
+            root: guard.as_ref().and_then(|index| scanner::build_ui_tree(index, ROOT_ID)),
+        }
+    })
+    .await
+    .map_err(AppError::from)
+}
+
+/// Recent log lines for bug reports, oldest first
+#[tauri::command]
+fn get_recent_logs(limit: Option<usize>, min_level: Option<String>) -> AppResult<Vec<LogEntry>> {
+    logging::recent_logs(limit.unwrap_or(500), min_level.as_deref())
+}
+
+/// Change the log verbosity ("error", "warn", "info", "debug", "trace")
+#[tauri::command]
+fn set_log_level(app_handle: AppHandle, state: State<'_, AppState>, level: String) -> AppResult<()> {
+    let update = SettingsUpdate {
+        log_level: Some(level),
+        ..Default::default()
+    };
+    apply_settings(&app_handle, &state, update).map(|_| ())
+}
+
+/// Last reported watcher status (mode, coverage, errors)
+#[tauri::command]
+fn get_watcher_status(state: State<'_, AppState>) -> Option<WatcherStatus> {
+    state.watcher_status.lock().unwrap().clone()
+}
+
+/// Set the polling fallback interval, restarting the watcher to apply it
+#[tauri::command]
+fn set_watch_poll_interval(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    seconds: u64,
+) -> AppResult<()> {
+    let update = SettingsUpdate {
+        watch_poll_interval_secs: Some(seconds),
+        ..Default::default()
+    };
+    apply_settings(&app_handle, &state, update).map(|_| ())
+}
+
+#[tauri::command]
+fn get_refresh_policy(state: State<'_, AppState>) -> RefreshPolicy {
+    state.settings.read().unwrap().refresh.policy.clone()
+}
+
+/// Validate, store, and apply a new refresh policy
+#[tauri::command]
+fn set_refresh_policy(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    policy: RefreshPolicy,
+) -> AppResult<RefreshPolicy> {
+    let update = SettingsUpdate {
+        refresh_policy: Some(policy),
+        ..Default::default()
+    };
+    apply_settings(&app_handle, &state, update).map(|settings| settings.refresh_policy)
+}
+
+/// Pause or resume live updates without changing the rest of the policy
+#[tauri::command]
+fn set_live_updates_paused(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    paused: bool,
+) -> AppResult<RefreshPolicy> {
+    let mut policy = state.settings.read().unwrap().refresh.policy.clone();
+    policy.paused = paused;
+    set_refresh_policy(app_handle, state, policy)
+}
+
+#[tauri::command]
+fn get_settings(state: State<'_, AppState>) -> Settings {
+    state.settings.read().unwrap().settings.clone()
+}
+
+/// Validate, store, and apply the fields set in `update`, returning the
+/// resulting settings
+#[tauri::command]
+fn update_settings(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    update: SettingsUpdate,
+) -> AppResult<Settings> {
+    apply_settings(&app_handle, &state, update)
+}
+
+/// Shared path of every settings change: validate the merged settings,
+/// store what changed, apply it, and broadcast `settings-changed` so every
+/// window picks it up
+fn apply_settings(app: &AppHandle, state: &AppState, update: SettingsUpdate) -> AppResult<Settings> {
+    // Held from read to save so concurrent updates apply one after another
+    let mut current = state.settings.write().unwrap();
+    let previous = current.settings.clone();
+    let active = previous.clone().merge(update).compile()?;
+    let settings = active.settings.clone();
+    if settings == previous {
+        return Ok(settings);
+    }
+    settings.save(&previous)?;
+    if settings.log_level != previous.log_level {
+        logging::set_level(&settings.log_level)?;
+    }
+    *current = active;
+    drop(current);
+
+    if settings.watch_poll_interval_secs != previous.watch_poll_interval_secs {
+        let scan_path = state.current_scan_path.lock().unwrap().clone();
+        if let Some(scan_path) = scan_path {
+            start_watching(app, state, &scan_path);
+        }
+    }
+    // Resuming flushes whatever was collected while paused
+    if !settings.refresh_policy.paused && !state.pending_changes.lock().unwrap().is_empty() {
+        schedule_incremental_refresh(app);
+    }
+
+    let _ = app.emit("settings-changed", &settings);
+    Ok(settings)
+}
+
 /// Trigger an incremental refresh (best-effort)
 #[tauri::command]
-async fn refresh_incremental(app_handle: AppHandle) -> Result<(), String> {
+async fn refresh_incremental(app_handle: AppHandle) -> AppResult<()> {
     let state = app_handle.state::<AppState>();
     if let Some(scan_path) = state.current_scan_path.lock().unwrap().clone() {
-        let mut dirty = state.dirty_paths.lock().unwrap();
-        dirty.insert(PathBuf::from(scan_path));
+        if workspace::workspace_name(&scan_path).is_some() {
+            return Err(AppError::new(
+                ErrorCode::Unsupported,
+                "Workspaces can't be refreshed incrementally; rescan instead",
+            )
+            .with_path(scan_path));
+        }
+        let mut pending = state.pending_changes.lock().unwrap();
+        pending.dirty.insert(PathBuf::from(scan_path));
     }
-    perform_incremental_refresh(app_handle).await
+    perform_incremental_refresh(app_handle).await.map(|_| ())
 }
 
 /// Open folder picker dialog - returns the selected path
 #[tauri::command]
-async fn open_folder_dialog(app_handle: AppHandle) -> Result<Option<String>, String> {
+async fn open_folder_dialog(app_handle: AppHandle) -> AppResult<Option<String>> {
     use tokio::sync::oneshot;
 
     let (tx, rx) = oneshot::channel();
@@ -565,72 +1382,233 @@ async fn open_folder_dialog(app_handle: AppHandle) -> Result<Option<String>, Str
             let _ = tx.send(path);
         });
 
-    rx.await.map_err(|e| e.to_string())
+    rx.await
+        .map_err(|e| AppError::internal(format!("Folder dialog closed unexpectedly: {}", e)))
+}
+
+/// Cancel one scan job, or every user-started scan when no id is given
+/// (background refreshes keep running)
+#[tauri::command]
+fn cancel_scan(state: State<'_, AppState>, job_id: Option<JobId>) -> bool {
+    match job_id {
+        Some(id) => state.scan_manager.cancel(id),
+        None => state.scan_manager.cancel_kind(ScanJobKind::Full) > 0,
+    }
+}
+
+/// Pause one scan job, or every user-started scan when no id is given.
+/// Walker threads block until the scan is resumed or cancelled.
+#[tauri::command]
+fn pause_scan(state: State<'_, AppState>, job_id: Option<JobId>) -> bool {
+    match job_id {
+        Some(id) => state.scan_manager.set_paused(id, true),
+        None => state.scan_manager.set_paused_kind(ScanJobKind::Full, true) > 0,
+    }
+}
+
+#[tauri::command]
+fn resume_scan(state: State<'_, AppState>, job_id: Option<JobId>) -> bool {
+    match job_id {
+        Some(id) => state.scan_manager.set_paused(id, false),
+        None => state.scan_manager.set_paused_kind(ScanJobKind::Full, false) > 0,
+    }
+}
+
+/// Create or replace a workspace; scan it with `scan_directory` and the
+/// returned key
+#[tauri::command]
+fn save_workspace(name: String, roots: Vec<String>) -> AppResult<String> {
+    let mut workspace = Workspace { name: name.trim().to_string(), roots };
+    workspace.roots = workspace
+        .validate()?
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    cache::save_workspace(&workspace)?;
+    Ok(workspace.key())
 }
 
-/// Cancel ongoing scan
 #[tauri::command]
-fn cancel_scan(state: State<'_, AppState>) {
-    state.scanner_state.cancel();
+fn list_workspaces() -> Vec<Workspace> {
+    cache::list_workspaces()
+}
+
+#[tauri::command]
+fn delete_workspace(name: String) -> AppResult<()> {
+    cache::delete_workspace(&name)
+}
+
+/// Scans currently running (full scans and background refreshes)
+#[tauri::command]
+fn list_scan_jobs(state: State<'_, AppState>) -> Vec<ScanJobInfo> {
+    state.scan_manager.list()
 }
 
 /// Open path in Finder
 #[tauri::command]
-fn show_in_finder(path: String) -> Result<(), String> {
+fn show_in_finder(path: String) -> AppResult<()> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
             .args(["-R", &path])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open Finder", &e).with_path(&path))?;
     }
     Ok(())
 }
 
 /// Open file with default application
 #[tauri::command]
-fn open_file(path: String) -> Result<(), String> {
+fn open_file(path: String) -> AppResult<()> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open file", &e).with_path(&path))?;
     }
     Ok(())
 }
 
-/// Move file to trash (using safe trash crate, no shell injection risk)
+/// Move file to trash (using safe trash crate, no shell injection risk).
+/// Protected, unscanned or changed paths are refused; see `safety`. The
+/// size check runs as a `measure` job, so `cancel_scan` can stop it.
 #[tauri::command]
-fn move_to_trash(path: String) -> Result<(), String> {
-    let path_buf = std::path::PathBuf::from(&path);
+async fn move_to_trash(state: State<'_, AppState>, path: String) -> AppResult<()> {
+    let job = state.scan_manager.start(ScanJobKind::Measure, &path)?;
+    let current_index = state.current_index.clone();
+    let settings = state.settings.read().unwrap().clone();
+    let categories = state.category_table.read().unwrap().clone();
+    tokio::task::spawn_blocking(move || {
+        delete::trash_one(&current_index, &settings, &categories, &job.state(), &path).map(|_| ())
+    })
+    .await?
+}
 
-    if !path_buf.exists() {
-        return Err(format!("Path does not exist: {}", path));
+/// Move file to trash and log the delete (optional) with the size measured
+/// by the safety check
+#[tauri::command]
+async fn move_to_trash_logged(
+    state: State<'_, AppState>,
+    path: String,
+    scan_path: Option<String>,
+) -> AppResult<()> {
+    let job = state.scan_manager.start(ScanJobKind::Measure, &path)?;
+    let current_index = state.current_index.clone();
+    let settings = state.settings.read().unwrap().clone();
+    let categories = state.category_table.read().unwrap().clone();
+    let record = tokio::task::spawn_blocking(move || {
+        delete::trash_one(&current_index, &settings, &categories, &job.state(), &path)
+    })
+    .await??;
+
+    if let Some(scan_path) = scan_path {
+        if let Err(e) = cache::log_delete(&scan_path, &record) {
+            warn!(error = %e, "failed to log delete");
+        }
     }
 
-    trash::delete(&path_buf).map_err(|e| format!("Failed to move to trash: {}", e))
+    Ok(())
 }
 
-/// Move file to trash and log the delete (optional)
+/// Delete without the trash (optionally overwriting a file first) and log
+/// it under the given or current scan path. Refused unless turned on in
+/// settings; see `delete::delete_permanently`.
 #[tauri::command]
-fn move_to_trash_logged(
+async fn delete_permanently(
+    state: State<'_, AppState>,
     path: String,
     scan_path: Option<String>,
-    size_bytes: Option<u64>,
-) -> Result<(), String> {
-    let path_buf = std::path::PathBuf::from(&path);
-    if !path_buf.exists() {
-        return Err(format!("Path does not exist: {}", path));
+    overwrite: Option<bool>,
+) -> AppResult<()> {
+    let scan_path = scan_path.or_else(|| state.current_scan_path.lock().unwrap().clone());
+    let job = state.scan_manager.start(ScanJobKind::Measure, &path)?;
+    let current_index = state.current_index.clone();
+    let settings = state.settings.read().unwrap().clone();
+    let categories = state.category_table.read().unwrap().clone();
+    let record = tokio::task::spawn_blocking(move || {
+        delete::delete_permanently(
+            &current_index,
+            &settings,
+            &categories,
+            &job.state(),
+            &path,
+            overwrite.unwrap_or(false),
+        )
+    })
+    .await??;
+
+    if let Some(scan_path) = scan_path {
+        if let Err(e) = cache::log_delete(&scan_path, &record) {
+            warn!(error = %e, "failed to log permanent delete");
+        }
     }
+    Ok(())
+}
 
-    trash::delete(&path_buf).map_err(|e| format!("Failed to move to trash: {}", e))?;
+/// Dry run of a batch delete: checks every path, collapses paths inside
+/// other selected paths, and returns the plan with a token for
+/// `execute_delete`
+#[tauri::command]
+async fn plan_delete(state: State<'_, AppState>, paths: Vec<String>) -> AppResult<DeletePlan> {
+    let scan_path = state
+        .current_scan_path
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| AppError::new(ErrorCode::NoScan, "No scan loaded"))?;
+    let job = state.scan_manager.start(ScanJobKind::Measure, &scan_path)?;
+    let current_index = state.current_index.clone();
+    let settings = state.settings.read().unwrap().clone();
+    let plans = state.delete_plans.clone();
+    tokio::task::spawn_blocking(move || {
+        plans.plan(&current_index, &settings, &job.state(), &scan_path, &paths)
+    })
+    .await?
+}
 
-    if let (Some(scan_path), Some(size_bytes)) = (scan_path, size_bytes) {
-        let _ = cache::log_delete(&scan_path, &path, size_bytes);
-    }
+/// Trash everything in a plan from `plan_delete`, logged as one batch
+#[tauri::command]
+async fn execute_delete(state: State<'_, AppState>, token: String) -> AppResult<DeleteOutcome> {
+    let plan = state.delete_plans.take(&token)?;
+    let job = state.scan_manager.start(ScanJobKind::Measure, &plan.scan_path)?;
+    let scan_path = state.current_scan_path.lock().unwrap().clone();
+    let current_index = state.current_index.clone();
+    let settings = state.settings.read().unwrap().clone();
+    let categories = state.category_table.read().unwrap().clone();
+    tokio::task::spawn_blocking(move || {
+        delete::execute(
+            &current_index,
+            &settings,
+            &categories,
+            &job.state(),
+            scan_path.as_deref(),
+            plan,
+        )
+    })
+    .await?
+}
 
-    Ok(())
+/// Undo a logged delete by restoring the item from the trash, then refresh
+/// the part of the tree it returns to
+#[tauri::command]
+async fn restore_deleted(app_handle: AppHandle, log_id: u64) -> AppResult<DeleteLogEntry> {
+    let entry = tokio::task::spawn_blocking(move || delete::restore(log_id)).await??;
+
+    let state = app_handle.state::<AppState>();
+    let in_current_scan =
+        state.current_scan_path.lock().unwrap().as_deref() == Some(entry.scan_path.as_str());
+    if in_current_scan && workspace::workspace_name(&entry.scan_path).is_none() {
+        state
+            .pending_changes
+            .lock()
+            .unwrap()
+            .dirty
+            .insert(PathBuf::from(&entry.target_path));
+        *state.last_event_at.lock().unwrap() = Instant::now();
+        schedule_incremental_refresh(&app_handle);
+    }
+    Ok(entry)
 }
 
 /// Get disk list
@@ -695,13 +1673,13 @@ pub struct DiskSpaceInfo {
 
 /// Get disk space info for a specific path
 #[tauri::command]
-fn get_disk_info(path: String) -> Result<DiskSpaceInfo, String> {
+fn get_disk_info(path: String) -> AppResult<DiskSpaceInfo> {
     use std::path::Path;
 
-    let path = Path::new(&path);
-    if !path.exists() {
-        return Err("Path does not exist".to_string());
+    if !Path::new(&path).exists() {
+        return Err(AppError::not_found(&path));
     }
+    let path = Path::new(&path);
 
     #[cfg(target_os = "macos")]
     {
@@ -711,18 +1689,18 @@ fn get_disk_info(path: String) -> Result<DiskSpaceInfo, String> {
         let output = Command::new("df")
             .args(["-k", path.to_str().unwrap_or("")])
             .output()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to run df", &e))?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let lines: Vec<&str> = stdout.lines().collect();
 
         if lines.len() < 2 {
-            return Err("Failed to get disk info".to_string());
+            return Err(AppError::internal("Failed to get disk info"));
         }
 
         let parts: Vec<&str> = lines[1].split_whitespace().collect();
         if parts.len() < 6 {
-            return Err("Failed to parse disk info".to_string());
+            return Err(AppError::internal("Failed to parse disk info"));
         }
 
         // df -k outputs in 1K blocks
@@ -741,25 +1719,45 @@ fn get_disk_info(path: String) -> Result<DiskSpaceInfo, String> {
 
     #[cfg(not(target_os = "macos"))]
     {
-        Err("Disk info not supported on this platform".to_string())
+        Err(AppError::new(
+            ErrorCode::Unsupported,
+            "Disk info not supported on this platform",
+        ))
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Logging starts before settings load so their warnings are recorded
+    let _log_guard = logging::init(&Settings::default().log_level);
+    let settings = Settings::load();
+    if let Err(e) = logging::apply_saved_level(&settings.log_level) {
+        warn!(error = %e, "failed to apply saved log level");
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(AppState::default())
+        .manage(AppState::new(settings))
         .invoke_handler(tauri::generate_handler![
             open_folder_dialog,
             scan_directory,
             load_from_cache,
             cancel_scan,
+            pause_scan,
+            resume_scan,
+            list_scan_jobs,
+            save_workspace,
+            list_workspaces,
+            delete_workspace,
             show_in_finder,
             open_file,
             move_to_trash,
             move_to_trash_logged,
+            delete_permanently,
+            plan_delete,
+            execute_delete,
+            restore_deleted,
             get_disks,
             get_disk_info,
             check_cache,
@@ -767,7 +1765,25 @@ pub fn run() {
             clear_all_caches,
             get_scan_history,
             get_delete_log,
+            get_delete_stats,
             refresh_incremental,
+            resync_tree,
+            set_watch_poll_interval,
+            get_watcher_status,
+            get_recent_logs,
+            set_log_level,
+            get_refresh_policy,
+            set_refresh_policy,
+            set_live_updates_paused,
+            get_settings,
+            update_settings,
+            search,
+            get_top_items,
+            get_type_stats,
+            get_age_analysis,
+            get_owner_usage,
+            get_category_overrides,
+            set_category_override,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
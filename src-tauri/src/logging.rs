@@ -0,0 +1,356 @@
+//! Logging: leveled output to stderr and a rotating file, plus a buffer of
+//! recent lines the UI can fetch for bug reports.
+//!
+//! Scans run inside a `scan` span with one `scan_phase` span per phase. A
+//! layer records how long each phase span was open, and the scanner builds
+//! its `ScanMetrics` from those timings, so the metrics and the log always
+//! agree. The timings layer sits outside the level filter, so metrics are
+//! recorded even when the log is set to `warn`.
+
+use crate::error::{AppError, AppResult};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Layer, Registry};
+
+/// Overrides the saved level at startup (e.g. `SPACEVIEW_LOG=debug`)
+const LOG_LEVEL_ENV: &str = "SPACEVIEW_LOG";
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::INFO;
+
+/// Daily log files kept in the log directory
+const MAX_LOG_FILES: usize = 7;
+/// Lines kept in memory for `recent_logs`
+const RECENT_CAPACITY: usize = 2000;
+
+/// Span names the timings layer looks for; the scanner must use these
+const SCAN_SPAN: &str = "scan";
+const PHASE_SPAN: &str = "scan_phase";
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+static RECENT: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+static PHASE_TIMINGS: OnceLock<Mutex<HashMap<u64, PhaseTimings>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Unix epoch milliseconds
+    pub timestamp_ms: u64,
+    pub level: String,
+    pub target: String,
+    /// Enclosing spans, outermost first (e.g. "scan:scan_phase")
+    pub spans: String,
+    /// The message followed by the event's fields as `key=value`
+    pub message: String,
+}
+
+/// How long each phase span of one scan was open, keyed by phase name
+#[derive(Debug, Clone, Default)]
+pub struct PhaseTimings(HashMap<String, Duration>);
+
+impl PhaseTimings {
+    /// Duration of `phase`, zero if it never ran
+    pub fn get(&self, phase: &str) -> Duration {
+        self.0.get(phase).copied().unwrap_or_default()
+    }
+}
+
+/// Directory of the rotating log files
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|p| p.join("spaceview").join("logs"))
+}
+
+/// Install the global subscriber at `level`, unless the environment
+/// overrides it. Keep the returned guard alive for the life of the app;
+/// dropping it flushes and stops the file writer.
+pub fn init(level: &str) -> Option<WorkerGuard> {
+    let level = env_level()
+        .or_else(|| LevelFilter::from_str(level).ok())
+        .unwrap_or(DEFAULT_LEVEL);
+    let (level_filter, handle) = reload::Layer::new(level);
+
+    let (file_writer, guard) = match log_dir().map(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("spaceview")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+    }) {
+        Some(Ok(appender)) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(writer), Some(guard))
+        }
+        Some(Err(e)) => {
+            eprintln!("[Log] Could not open log file: {}", e);
+            (None, None)
+        }
+        None => (None, None),
+    };
+
+    // Span fields are formatted once, by the first layer to see the span,
+    // so the plain file layer goes first to keep color codes out of the file
+    let file_output = file_writer.map(|writer| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+    });
+    let output = Layer::and_then(
+        file_output,
+        tracing_subscriber::fmt::layer().with_writer(std::io::stderr),
+    )
+    .and_then(RecentLogs)
+    .with_filter(level_filter);
+
+    let installed = tracing_subscriber::registry()
+        .with(output)
+        .with(PhaseTimingLayer)
+        .try_init();
+    if installed.is_err() {
+        return None;
+    }
+    let _ = LEVEL_HANDLE.set(handle);
+    tracing::info!(level = %level, dir = ?log_dir(), "logging started");
+    guard
+}
+
+fn env_level() -> Option<LevelFilter> {
+    std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .and_then(|l| LevelFilter::from_str(&l).ok())
+}
+
+fn parse_level(level: &str) -> AppResult<LevelFilter> {
+    LevelFilter::from_str(level)
+        .map_err(|_| AppError::invalid(format!("Unknown log level: {}", level)))
+}
+
+/// Check that `level` names a verbosity `set_level` accepts
+pub fn check_level(level: &str) -> AppResult<()> {
+    parse_level(level).map(|_| ())
+}
+
+/// Change the verbosity of every output
+pub fn set_level(level: &str) -> AppResult<()> {
+    let filter = parse_level(level)?;
+    let handle = LEVEL_HANDLE
+        .get()
+        .ok_or_else(|| AppError::internal("Logging is not initialized"))?;
+    handle
+        .reload(filter)
+        .map_err(|e| AppError::internal(format!("Failed to change log level: {}", e)))?;
+    tracing::info!(level = %filter, "log level changed");
+    Ok(())
+}
+
+/// Switch to the saved level once settings are loaded (logging starts
+/// before that), unless the environment overrides it
+pub fn apply_saved_level(level: &str) -> AppResult<()> {
+    let filter = parse_level(level)?;
+    if env_level().is_some() || LevelFilter::current() == filter {
+        return Ok(());
+    }
+    set_level(level)
+}
+
+/// The last `limit` buffered lines at `min_level` or more severe, oldest first
+pub fn recent_logs(limit: usize, min_level: Option<&str>) -> AppResult<Vec<LogEntry>> {
+    let min_level = match min_level {
+        Some(level) => Some(
+            Level::from_str(level)
+                .map_err(|_| AppError::invalid(format!("Unknown log level: {}", level)))?,
+        ),
+        None => None,
+    };
+    let recent = RECENT.lock().unwrap();
+    let mut entries: Vec<LogEntry> = recent
+        .iter()
+        .rev()
+        .filter(|e| {
+            min_level.is_none_or(|min| Level::from_str(&e.level).is_ok_and(|l| l <= min))
+        })
+        .take(limit)
+        .cloned()
+        .collect();
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Phase timings recorded so far for a scan job. Call it inside the job's
+/// `scan` span; the timings are dropped when that span closes.
+pub fn phase_timings(job_id: u64) -> PhaseTimings {
+    timings_map()
+        .lock()
+        .unwrap()
+        .get(&job_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn timings_map() -> &'static Mutex<HashMap<u64, PhaseTimings>> {
+    PHASE_TIMINGS.get_or_init(Default::default)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Collects an event's message and fields into one line
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+/// Keeps the last `RECENT_CAPACITY` lines in memory
+struct RecentLogs;
+
+impl<S> Layer<S> for RecentLogs
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| span.name())
+                    .collect::<Vec<_>>()
+                    .join(":")
+            })
+            .unwrap_or_default();
+        let metadata = event.metadata();
+        let entry = LogEntry {
+            timestamp_ms: now_ms(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            spans,
+            message: visitor.message + &visitor.fields,
+        };
+
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+}
+
+/// Reads `job_id` from a scan span and `phase` from a phase span
+#[derive(Default)]
+struct SpanFields {
+    job_id: Option<u64>,
+    phase: Option<String>,
+}
+
+impl Visit for SpanFields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "job_id" {
+            self.job_id = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "phase" {
+            self.phase = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+/// Extension stored on a scan span
+struct ScanJob(u64);
+
+/// Extension stored on a phase span
+struct PhaseStart {
+    phase: String,
+    started: Instant,
+}
+
+/// Records how long each `scan_phase` span is open, per scan job
+struct PhaseTimingLayer;
+
+impl<S> Layer<S> for PhaseTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        if name != SCAN_SPAN && name != PHASE_SPAN {
+            return;
+        }
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        if name == SCAN_SPAN {
+            if let Some(job_id) = fields.job_id {
+                span.extensions_mut().insert(ScanJob(job_id));
+            }
+        } else if let Some(phase) = fields.phase {
+            span.extensions_mut().insert(PhaseStart {
+                phase,
+                started: Instant::now(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let extensions = span.extensions();
+        if let Some(ScanJob(job_id)) = extensions.get::<ScanJob>() {
+            timings_map().lock().unwrap().remove(job_id);
+            return;
+        }
+        let Some(start) = extensions.get::<PhaseStart>() else { return };
+        let Some(job_id) = span
+            .scope()
+            .skip(1)
+            .find_map(|parent| parent.extensions().get::<ScanJob>().map(|job| job.0))
+        else {
+            return;
+        };
+        let mut timings = timings_map().lock().unwrap();
+        *timings
+            .entry(job_id)
+            .or_default()
+            .0
+            .entry(start.phase.clone())
+            .or_default() += start.started.elapsed();
+    }
+}
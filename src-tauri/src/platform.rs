@@ -0,0 +1,206 @@
+//! Thin wrappers over OS facilities that std doesn't expose.
+
+use std::ffi::{CStr, CString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// Resolve a uid to a login name through the local passwd database (NSS)
+pub fn username_for_uid(uid: u32) -> Option<String> {
+    let mut buf: Vec<libc::c_char> = vec![0; 1024];
+    // SAFETY: passwd is a plain C struct; all-zero is a valid initial value
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+    loop {
+        // SAFETY: every pointer references a live local buffer of the stated length
+        let rc = unsafe {
+            libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+        };
+        if rc == libc::ERANGE && buf.len() < 1 << 20 {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if rc != 0 || result.is_null() || pwd.pw_name.is_null() {
+            return None;
+        }
+        // SAFETY: on success pw_name points into `buf` and is NUL-terminated
+        let name = unsafe { CStr::from_ptr(pwd.pw_name) };
+        return Some(name.to_string_lossy().into_owned());
+    }
+}
+
+fn statfs(path: &Path) -> Option<libc::statfs> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statfs is a plain C struct; all-zero is a valid initial value
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is NUL-terminated and stat is a live, writable struct
+    let rc = unsafe { libc::statfs(c_path.as_ptr(), &mut stat) };
+    (rc == 0).then_some(stat)
+}
+
+/// Name of the filesystem type when `path` is on a network share or FUSE
+/// mount, where changes made elsewhere never produce native watch events
+#[cfg(target_os = "linux")]
+pub fn remote_filesystem(path: &Path) -> Option<&'static str> {
+    const REMOTE_MAGIC: [(u32, &str); 8] = [
+        (0x0000_6969, "nfs"),
+        (0x0000_517B, "smb"),
+        (0xFF53_4D42, "cifs"),
+        (0xFE53_4D42, "smb2"),
+        (0x6573_5546, "fuse"),
+        (0x0102_1997, "9p"),
+        (0x5346_414F, "afs"),
+        (0x00C3_6400, "ceph"),
+    ];
+    let stat = statfs(path)?;
+    // f_type is a signed word whose width varies by architecture
+    let magic = (stat.f_type as u64 & 0xFFFF_FFFF) as u32;
+    REMOTE_MAGIC
+        .iter()
+        .find(|(m, _)| *m == magic)
+        .map(|(_, name)| *name)
+}
+
+/// Name of the filesystem type when `path` is on a network share or FUSE
+/// mount, where changes made elsewhere never produce native watch events
+#[cfg(target_os = "macos")]
+pub fn remote_filesystem(path: &Path) -> Option<&'static str> {
+    const REMOTE_TYPES: [&str; 8] = [
+        "nfs", "smbfs", "afpfs", "webdav", "cifs", "macfuse", "osxfuse", "fusefs",
+    ];
+    let stat = statfs(path)?;
+    // SAFETY: the kernel NUL-terminates f_fstypename
+    let name = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    let name = name.to_str().ok()?;
+    REMOTE_TYPES.iter().find(|t| **t == name).copied()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn remote_filesystem(_path: &Path) -> Option<&'static str> {
+    None
+}
+
+/// Name of the filesystem type when `path` is on a copy-on-write
+/// filesystem, where overwriting a file writes new blocks and leaves the old
+/// data on disk
+#[cfg(target_os = "linux")]
+pub fn copy_on_write_filesystem(path: &Path) -> Option<&'static str> {
+    const COW_MAGIC: [(u32, &str); 3] = [
+        (0x9123_683E, "btrfs"),
+        (0x2FC1_2FC1, "zfs"),
+        (0xCA45_1A4E, "bcachefs"),
+    ];
+    let stat = statfs(path)?;
+    let magic = (stat.f_type as u64 & 0xFFFF_FFFF) as u32;
+    COW_MAGIC
+        .iter()
+        .find(|(m, _)| *m == magic)
+        .map(|(_, name)| *name)
+}
+
+/// Name of the filesystem type when `path` is on a copy-on-write
+/// filesystem, where overwriting a file writes new blocks and leaves the old
+/// data on disk
+#[cfg(target_os = "macos")]
+pub fn copy_on_write_filesystem(path: &Path) -> Option<&'static str> {
+    const COW_TYPES: [&str; 2] = ["apfs", "zfs"];
+    let stat = statfs(path)?;
+    // SAFETY: the kernel NUL-terminates f_fstypename
+    let name = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    let name = name.to_str().ok()?;
+    COW_TYPES.iter().find(|t| **t == name).copied()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn copy_on_write_filesystem(_path: &Path) -> Option<&'static str> {
+    None
+}
+
+/// The user-wide inotify watch limit (`fs.inotify.max_user_watches`), or
+/// None where watches aren't a per-directory resource
+#[cfg(target_os = "linux")]
+pub fn inotify_max_user_watches() -> Option<u64> {
+    std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn inotify_max_user_watches() -> Option<u64> {
+    None
+}
+
+/// Lower the calling thread's priority: the idle IO class and/or a nice
+/// level. Best effort; returns false if the OS refused any part of it.
+#[cfg(target_os = "linux")]
+pub fn lower_thread_priority(io_idle: bool, nice: Option<i32>) -> bool {
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+
+    let mut ok = true;
+    if io_idle {
+        // SAFETY: ioprio_set takes plain integers; who = 0 means the calling thread
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0 as libc::c_long,
+                IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            )
+        };
+        ok &= rc == 0;
+    }
+    if let Some(nice) = nice {
+        // On Linux the nice value is per thread, keyed by tid
+        // SAFETY: gettid and setpriority take and return plain integers
+        let rc = unsafe {
+            libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, nice)
+        };
+        ok &= rc == 0;
+    }
+    ok
+}
+
+/// Lower the calling thread's priority. macOS has no per-thread nice, so
+/// either request puts the thread in the background band, which throttles
+/// both its CPU and disk IO.
+#[cfg(target_os = "macos")]
+pub fn lower_thread_priority(io_idle: bool, nice: Option<i32>) -> bool {
+    if !io_idle && nice.unwrap_or(0) <= 0 {
+        return true;
+    }
+    // SAFETY: setpriority takes plain integers; who = 0 means the calling thread
+    unsafe { libc::setpriority(libc::PRIO_DARWIN_THREAD, 0, libc::PRIO_DARWIN_BG) == 0 }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn lower_thread_priority(_io_idle: bool, _nice: Option<i32>) -> bool {
+    false
+}
+
+/// Whether `path` is the root of a mounted filesystem
+pub fn is_mount_point(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let Ok(meta) = std::fs::metadata(path) else { return false };
+    match path.parent().and_then(|p| std::fs::metadata(p).ok()) {
+        Some(parent) => parent.dev() != meta.dev() || parent.ino() == meta.ino(),
+        None => true,
+    }
+}
+
+/// Inodes in use on the filesystem holding `path`, or None when the
+/// filesystem doesn't track them (btrfs and some network mounts report 0)
+pub fn used_inodes(path: &Path) -> Option<u64> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs is a plain C struct; all-zero is a valid initial value
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is NUL-terminated and stat is a live, writable struct
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 || stat.f_files == 0 {
+        return None;
+    }
+    Some((stat.f_files as u64).saturating_sub(stat.f_ffree as u64))
+}
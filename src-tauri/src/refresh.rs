@@ -0,0 +1,145 @@
+//! Incremental refresh policy.
+//!
+//! Controls how watcher events turn into refreshes: how long to wait for a
+//! burst of events to settle, when to give up on subtree rescans and rescan
+//! everything, which paths to ignore, and whether live updates are paused.
+//! The policy is stored with the other settings.
+
+use crate::error::{AppError, AppResult};
+use crate::scanner::ScanOptions;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshPolicy {
+    /// Quiet period after the last event before a refresh starts
+    pub debounce_ms: u64,
+    /// Longest a steady stream of events can defer a refresh
+    pub max_wait_ms: u64,
+    /// More dirty directories than this trigger a full rescan
+    pub full_rescan_threshold: usize,
+    /// Gitignore-style globs relative to the scan root: patterns without a
+    /// `/` match a name at any depth, and a match covers everything below it
+    pub ignore_patterns: Vec<String>,
+    /// Keep collecting events but hold refreshes until resumed
+    pub paused: bool,
+    /// Resource limits for refresh scans
+    pub scan_options: ScanOptions,
+}
+
+impl Default for RefreshPolicy {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 800,
+            max_wait_ms: 10_000,
+            full_rescan_threshold: 40,
+            ignore_patterns: Vec::new(),
+            paused: false,
+            scan_options: ScanOptions::low_impact(),
+        }
+    }
+}
+
+impl RefreshPolicy {
+    /// Check bounds and compile the ignore patterns
+    pub fn compile(self) -> AppResult<ActiveRefreshPolicy> {
+        if !(50..=60_000).contains(&self.debounce_ms) {
+            return Err(AppError::invalid("Debounce must be between 50 ms and 60 s"));
+        }
+        if self.max_wait_ms < self.debounce_ms {
+            return Err(AppError::invalid("Max wait must not be shorter than the debounce"));
+        }
+        if self.full_rescan_threshold == 0 {
+            return Err(AppError::invalid("Full rescan threshold must be at least 1"));
+        }
+        self.scan_options.validate()?;
+        let ignore = PathPatterns::compile(&self.ignore_patterns, "ignore")?;
+        Ok(ActiveRefreshPolicy { policy: self, ignore })
+    }
+}
+
+/// Gitignore-style globs matched against paths relative to a scan root.
+///
+/// Patterns without a `/` match a name at any depth, others are anchored at
+/// the root, and a match covers everything below the matched path.
+#[derive(Clone)]
+pub struct PathPatterns {
+    set: GlobSet,
+}
+
+impl PathPatterns {
+    /// Compile `patterns`; `what` names them in error messages
+    pub fn compile(patterns: &[String], what: &str) -> AppResult<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let trimmed = pattern.trim().trim_start_matches('/').trim_end_matches('/');
+            if trimmed.is_empty() {
+                continue;
+            }
+            let anchored = if trimmed.contains('/') {
+                trimmed.to_string()
+            } else {
+                format!("**/{}", trimmed)
+            };
+            let glob = GlobBuilder::new(&anchored)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| {
+                    AppError::invalid(format!("Invalid {} pattern '{}': {}", what, pattern, e))
+                })?;
+            builder.add(glob);
+        }
+        let set = builder
+            .build()
+            .map_err(|e| AppError::invalid(format!("Invalid {} patterns: {}", what, e)))?;
+        Ok(Self { set })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Whether `path` (under `root`) or any directory above it matches
+    pub fn matches(&self, root: &Path, path: &Path) -> bool {
+        if self.set.is_empty() {
+            return false;
+        }
+        let Ok(rel) = path.strip_prefix(root) else { return false };
+        rel.ancestors()
+            .filter(|p| !p.as_os_str().is_empty())
+            .any(|p| self.set.is_match(p))
+    }
+
+    /// Whether `path` itself matches; enough when the walk prunes matched
+    /// directories before descending
+    pub fn matches_entry(&self, root: &Path, path: &Path) -> bool {
+        match path.strip_prefix(root) {
+            Ok(rel) => !rel.as_os_str().is_empty() && self.set.is_match(rel),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Default for PathPatterns {
+    fn default() -> Self {
+        Self {
+            set: GlobSet::empty(),
+        }
+    }
+}
+
+/// A validated policy with its ignore patterns compiled
+#[derive(Clone, Default)]
+pub struct ActiveRefreshPolicy {
+    pub policy: RefreshPolicy,
+    ignore: PathPatterns,
+}
+
+impl ActiveRefreshPolicy {
+    /// Whether events for `path` (under `root`) should be dropped
+    pub fn is_ignored(&self, root: &Path, path: &Path) -> bool {
+        self.ignore.matches(root, path)
+    }
+}
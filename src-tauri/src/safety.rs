@@ -0,0 +1,375 @@
+//! Checks run before anything is moved to the trash.
+//!
+//! A target must be an absolute path strictly inside one of the current
+//! scan's roots, must not be a system location, a home folder or one of the
+//! user's protected paths, and must not be a mount point. Its size on disk
+//! must also still be close to what the scan recorded: a large difference
+//! means the tree is stale and the user isn't deleting what they see. A
+//! directory too large to re-measure in full skips that comparison.
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::index::ScanIndex;
+use crate::platform;
+use crate::scanner::{MeasuredSize, Scanner, ScannerState};
+use crate::settings::ActiveSettings;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tracing::warn;
+
+/// Locations that can't be deleted themselves, though their contents can
+const PROTECTED_EXACT: &[&str] = &[
+    "/",
+    "/Applications",
+    "/Library",
+    "/Users",
+    "/Volumes",
+    "/home",
+    "/media",
+    "/mnt",
+    "/opt",
+    "/private",
+    "/root",
+    "/srv",
+    "/tmp",
+    "/var",
+];
+
+/// Locations protected along with everything inside them
+const PROTECTED_TREES: &[&str] = &[
+    "/System",
+    "/bin",
+    "/boot",
+    "/dev",
+    "/etc",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/private/etc",
+    "/private/var/db",
+    "/proc",
+    "/run",
+    "/sbin",
+    "/sys",
+    "/usr",
+];
+
+/// Size drift allowed between the scan and the disk, whichever is larger
+const SIZE_TOLERANCE_RATIO: u64 = 4;
+const SIZE_TOLERANCE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// A target that passed every check
+#[derive(Debug, Clone)]
+pub struct DeleteTarget {
+    pub path: PathBuf,
+    /// Size measured just now
+    pub size_bytes: u64,
+}
+
+/// Run every check on `path` against the current index and settings.
+///
+/// Directories are re-measured with a fresh walk, so this can take a while
+/// on a large folder; call it off the async runtime. Cancelling `cancel`
+/// stops the walk and fails the check.
+pub fn check_delete(
+    index: &RwLock<Option<ScanIndex>>,
+    settings: &ActiveSettings,
+    cancel: &Arc<ScannerState>,
+    path: &str,
+) -> AppResult<DeleteTarget> {
+    let target = normalize(path)?;
+    check_protected(&target, &settings.settings.protected_paths)?;
+
+    let (root, indexed_size) = {
+        let guard = index.read().unwrap();
+        let index = guard
+            .as_ref()
+            .ok_or_else(|| AppError::new(ErrorCode::NoScan, "No scan loaded"))?;
+        let root = index
+            .scan_roots()
+            .into_iter()
+            .find(|root| target.starts_with(root))
+            .ok_or_else(|| {
+                AppError::new(
+                    ErrorCode::OutsideScanRoot,
+                    format!("Not inside the current scan: {}", path),
+                )
+                .with_path(path)
+            })?;
+        if target == root {
+            return Err(AppError::new(
+                ErrorCode::OutsideScanRoot,
+                format!("Refusing to delete a scan root: {}", path),
+            )
+            .with_path(path));
+        }
+        let node = index
+            .find(&target)
+            .and_then(|id| index.get(id))
+            .ok_or_else(|| {
+                AppError::new(
+                    ErrorCode::NotIndexed,
+                    format!("Path not in the scan: {}", path),
+                )
+                .with_path(path)
+            })?;
+        (root, node.size)
+    };
+
+    let meta = std::fs::symlink_metadata(&target)
+        .map_err(|e| AppError::io("Failed to read target", &e).with_path(path))?;
+    // A symlink is removed on its own, never what it points to
+    let is_dir = meta.is_dir();
+    if is_dir && platform::is_mount_point(&target) {
+        return Err(AppError::new(
+            ErrorCode::MountPoint,
+            format!("Refusing to delete a mount point: {}", path),
+        )
+        .with_path(path));
+    }
+
+    let (size_bytes, size_known) = if is_dir {
+        let measured = measure_dir(&target, &root, settings, cancel)?;
+        if !measured.complete {
+            warn!(path, "too large to re-measure; skipping the size check");
+        }
+        (measured.bytes, measured.complete)
+    } else {
+        // Extra hard links were counted as zero by the scan
+        (meta.len(), !has_other_links(&meta))
+    };
+    if size_known && !size_close(indexed_size, size_bytes) {
+        return Err(AppError::new(
+            ErrorCode::SizeChanged,
+            format!(
+                "Size changed since the scan ({} bytes then, {} now); rescan before deleting",
+                indexed_size, size_bytes
+            ),
+        )
+        .with_path(path));
+    }
+
+    Ok(DeleteTarget {
+        path: target,
+        size_bytes,
+    })
+}
+
+/// Refuse a directory with another filesystem mounted somewhere inside it.
+///
+/// A recursive delete crosses into mounts, so deleting `~/old` would also
+/// empty a disk or share mounted at `~/old/backup`. Directories that can't
+/// be read are refused too, since what is mounted below them is unknown.
+pub fn check_no_nested_mounts(dir: &Path) -> AppResult<()> {
+    use std::os::unix::fs::MetadataExt;
+    let read_failed = |e: std::io::Error, path: &Path| {
+        AppError::io("Failed to read directory", &e).with_path(path)
+    };
+
+    let dev = std::fs::symlink_metadata(dir).map_err(|e| read_failed(e, dir))?.dev();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        for entry in std::fs::read_dir(&current).map_err(|e| read_failed(e, &current))? {
+            let entry = entry.map_err(|e| read_failed(e, &current))?;
+            // Neither file_type nor metadata follows symlinks
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let path = entry.path();
+            let meta = entry.metadata().map_err(|e| read_failed(e, &path))?;
+            if meta.dev() != dev {
+                return Err(AppError::new(
+                    ErrorCode::MountPoint,
+                    format!(
+                        "Refusing to delete {}: a filesystem is mounted at {}",
+                        dir.display(),
+                        path.display()
+                    ),
+                )
+                .with_path(dir));
+            }
+            stack.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `path` as an absolute path without `.` segments or trailing slashes
+pub fn normalize(path: &str) -> AppResult<PathBuf> {
+    let raw = Path::new(path);
+    if !raw.is_absolute() {
+        return Err(AppError::invalid(format!("Path must be absolute: {}", path)).with_path(path));
+    }
+    if raw.components().any(|c| c == Component::ParentDir) {
+        return Err(
+            AppError::invalid(format!("Path must not contain '..': {}", path)).with_path(path),
+        );
+    }
+    Ok(raw.components().collect())
+}
+
+/// Refuse built-in and user-configured protected locations. The check is
+/// repeated with the parent directory resolved, so a symlinked parent can't
+/// lead into a protected tree.
+fn check_protected(path: &Path, user_protected: &[String]) -> AppResult<()> {
+    let resolved = path
+        .parent()
+        .and_then(|parent| parent.canonicalize().ok())
+        .zip(path.file_name())
+        .map(|(parent, name)| parent.join(name));
+    for candidate in std::iter::once(path).chain(resolved.as_deref()) {
+        if let Some(reason) = protection_reason(candidate, user_protected) {
+            return Err(AppError::new(
+                ErrorCode::ProtectedPath,
+                format!("Refusing to delete {}: {}", candidate.display(), reason),
+            )
+            .with_path(path));
+        }
+    }
+    Ok(())
+}
+
+fn protection_reason(path: &Path, user_protected: &[String]) -> Option<&'static str> {
+    if PROTECTED_EXACT.iter().any(|p| path == Path::new(p)) {
+        return Some("system location");
+    }
+    if PROTECTED_TREES.iter().any(|p| path.starts_with(p)) {
+        return Some("inside a system location");
+    }
+    if let Some(home) = dirs::home_dir() {
+        if home.starts_with(path) {
+            return Some("home folder");
+        }
+    }
+    if user_protected.iter().any(|p| path.starts_with(p)) {
+        return Some("protected in settings");
+    }
+    None
+}
+
+/// Total `dir` the way the scan did: the user's scan options, with
+/// excludes matched from `root`
+fn measure_dir(
+    dir: &Path,
+    root: &Path,
+    settings: &ActiveSettings,
+    cancel: &Arc<ScannerState>,
+) -> AppResult<MeasuredSize> {
+    Scanner::new(cancel.clone())
+        .with_options(settings.settings.scan_options.clone())
+        .with_excludes(settings.excludes.clone(), Some(root.to_path_buf()))
+        .measure(dir)
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorCode::Cancelled,
+                format!("Cancelled while measuring {}", dir.display()),
+            )
+            .with_path(dir)
+        })
+}
+
+fn size_close(indexed: u64, current: u64) -> bool {
+    let tolerance = (indexed / SIZE_TOLERANCE_RATIO).max(SIZE_TOLERANCE_BYTES);
+    indexed.abs_diff(current) <= tolerance
+}
+
+fn has_other_links(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.nlink() > 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn reason(path: &str, user_protected: &[&str]) -> Option<&'static str> {
+        let user_protected: Vec<String> = user_protected.iter().map(|p| p.to_string()).collect();
+        protection_reason(Path::new(path), &user_protected)
+    }
+
+    #[test]
+    fn size_close_allows_the_larger_tolerance() {
+        // Small items get the fixed allowance
+        assert!(size_close(0, 64 * MIB));
+        assert!(!size_close(0, 64 * MIB + 1));
+        assert!(size_close(10 * MIB, 0));
+        // Large items get a quarter of their size, either way
+        assert!(size_close(1000 * MIB, 1250 * MIB));
+        assert!(size_close(1000 * MIB, 750 * MIB));
+        assert!(!size_close(1000 * MIB, 1251 * MIB));
+        assert!(!size_close(1000 * MIB, 749 * MIB));
+    }
+
+    #[test]
+    fn system_locations_are_protected() {
+        assert_eq!(reason("/", &[]), Some("system location"));
+        assert_eq!(reason("/Users", &[]), Some("system location"));
+        assert_eq!(reason("/usr", &[]), Some("inside a system location"));
+        assert_eq!(reason("/usr/local/bin/tool", &[]), Some("inside a system location"));
+        assert_eq!(reason("/etc/hosts", &[]), Some("inside a system location"));
+        // Only the exact location is protected, not its contents
+        assert_eq!(reason("/tmp/build", &[]), None);
+        assert_eq!(reason("/mnt/disk/old", &[]), None);
+        // Prefixes match whole components
+        assert_eq!(reason("/usrdata", &[]), None);
+    }
+
+    #[test]
+    fn home_and_its_ancestors_are_protected() {
+        let Some(home) = dirs::home_dir() else { return };
+        let user_protected: Vec<String> = Vec::new();
+        // Some homes (/root) are system locations as well, so any reason will do
+        for path in home.ancestors() {
+            assert!(protection_reason(path, &user_protected).is_some(), "{}", path.display());
+        }
+        assert_eq!(protection_reason(&home.join("Downloads"), &user_protected), None);
+    }
+
+    #[test]
+    fn user_protected_paths_cover_their_contents() {
+        let protected = ["/data/photos"];
+        assert_eq!(reason("/data/photos", &protected), Some("protected in settings"));
+        assert_eq!(reason("/data/photos/2020/a.jpg", &protected), Some("protected in settings"));
+        assert_eq!(reason("/data/photos-old", &protected), None);
+        assert_eq!(reason("/data", &protected), None);
+    }
+
+    #[test]
+    fn check_protected_reports_the_error_code() {
+        let err = check_protected(Path::new("/etc/passwd"), &[]).unwrap_err();
+        assert_eq!(err.code, ErrorCode::ProtectedPath);
+        assert!(check_protected(Path::new("/data/photos-old"), &[]).is_ok());
+    }
+
+    #[test]
+    fn nested_mounts_are_refused() {
+        let dir = std::env::temp_dir().join(format!("spaceview-mounts-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("a/b")).unwrap();
+        std::fs::write(dir.join("a/f"), b"x").unwrap();
+        let result = check_no_nested_mounts(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_ok());
+
+        // /dev normally has devpts or a tmpfs mounted inside it
+        #[cfg(target_os = "linux")]
+        {
+            let mounts = std::fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+            let nested = mounts
+                .lines()
+                .filter_map(|line| line.split(' ').nth(4))
+                .any(|mount| mount.starts_with("/dev/"));
+            if nested {
+                let err = check_no_nested_mounts(Path::new("/dev")).unwrap_err();
+                assert_eq!(err.code, ErrorCode::MountPoint);
+            }
+        }
+    }
+
+    #[test]
+    fn normalize_rejects_relative_and_parent_paths() {
+        assert_eq!(normalize("/a/./b/").unwrap(), PathBuf::from("/a/b"));
+        assert_eq!(normalize("a/b").unwrap_err().code, ErrorCode::InvalidInput);
+        assert_eq!(normalize("/a/../etc").unwrap_err().code, ErrorCode::InvalidInput);
+    }
+}
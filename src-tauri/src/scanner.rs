@@ -7,20 +7,31 @@
 //! 4. Streaming results with crossbeam channels
 //! 5. Bottom-up size calculation with iterative post-order
 
-use crossbeam_channel::bounded;
+use crate::analysis::{self, ExtensionStat};
+use crate::error::{AppError, AppResult};
+use crate::index::{IndexNode, NodeId, ScanIndex, ROOT_ID};
+use crate::logging;
+use crate::platform;
+use crate::refresh::PathPatterns;
+use crate::workspace;
+use crossbeam_channel::{bounded, RecvTimeoutError};
 use dashmap::{DashMap, DashSet};
 use ignore::{WalkBuilder, WalkState};
 use serde::{Deserialize, Serialize};
+use std::ffi::{OsStr, OsString};
 use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, info_span, warn};
 
-/// Performance metrics for scan analysis
+/// Performance metrics for scan analysis; phase times come from the scan's
+/// `scan_phase` spans
 #[derive(Debug, Clone, Serialize)]
 pub struct ScanMetrics {
+    pub job_id: u64,
     pub total_time_ms: u64,
     pub walk_time_ms: u64,
     pub relation_time_ms: u64,
@@ -57,6 +68,10 @@ fn get_memory_usage() -> u64 {
 const MAX_DEPTH: usize = 64;      // Maximum tree depth (SpaceSniffer-style)
 const MAX_TOTAL_NODES: usize = 250_000;  // Absolute limit on total nodes in tree
 const MAX_SCANNED_NODES: usize = 1_000_000; // Hard cap to avoid exhausting RAM during walk
+const PROGRESS_POLL: Duration = Duration::from_millis(200); // How often the reporter notices pause/resume
+const PARTIAL_INTERVAL: Duration = Duration::from_secs(1); // How often provisional sizes are published during the walk
+const PARTIAL_MAX_CHILDREN: usize = 200;     // Largest root children included in each partial result
+const WALK_SHARE: f64 = 0.9; // Share of scan time spent walking; the in-memory phases after it are quick
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileNode {
@@ -66,36 +81,120 @@ pub struct FileNode {
     pub size: u64,
     pub is_dir: bool,
     pub children: Vec<FileNode>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub extension: Option<String>,
     pub file_count: u64,
     pub dir_count: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub modified_at: Option<u64>,  // Unix timestamp in seconds
+    pub accessed_at: Option<u64>,  // Unix timestamp in seconds
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+/// Output of a completed scan: the capped UI tree plus the full index
+pub struct ScanResult {
+    pub root: FileNode,
+    pub index: ScanIndex,
+    pub extension_stats: Vec<ExtensionStat>,
+}
+
+/// Materialize the capped UI tree for a node of the index
+pub fn build_ui_tree(index: &ScanIndex, id: NodeId) -> Option<FileNode> {
+    let mut node_count = 0;
+    index.to_file_node(id, MAX_DEPTH, MAX_TOTAL_NODES, &mut node_count)
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ScanProgress {
+    pub job_id: u64,
     pub scanned_files: u64,
     pub scanned_dirs: u64,
     pub current_path: String,
     pub total_size: u64,
     pub is_complete: bool,
+    pub is_paused: bool,
     pub phase: String,  // "walking" | "relations" | "sizes" | "tree" | "complete"
+    /// Overall progress (0-100); None while walking without an estimate
+    pub percent: Option<f64>,
+    /// Progress within the current phase (0-1)
+    pub phase_progress: Option<f64>,
+    /// Estimated time left; None when unknown or paused
+    pub eta_ms: Option<u64>,
+    /// Files and directories visited per second during the walk
+    pub entries_per_sec: u64,
+    pub estimate_source: Option<EstimateSource>,
+}
+
+/// Provisional sizes of the root's immediate children, published while
+/// the walk is still running
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialScan {
+    pub job_id: u64,
+    pub root_path: String,
+    pub total_size: u64,
+    /// Largest first, at most PARTIAL_MAX_CHILDREN
+    pub children: Vec<PartialEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialEntry {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub is_dir: bool,
+    pub file_count: u64,
+    pub dir_count: u64,
+}
+
+/// Running totals for one child of the scan root
+#[derive(Default)]
+struct TopLevelStat {
+    size: u64,
+    is_dir: bool,
+    file_count: u64,
+    dir_count: u64,
+}
+
+/// Where a scan's expected size came from
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateSource {
+    /// Entry count of the last scan of the same path
+    PreviousScan,
+    /// Used inodes of the volume, for scans of a mount point
+    Inodes,
+}
+
+/// Expected number of entries (files plus directories) a scan will visit
+#[derive(Debug, Clone, Copy)]
+pub struct ScanEstimate {
+    pub entries: u64,
+    pub source: EstimateSource,
 }
 
 pub struct ScannerState {
     is_cancelled: AtomicBool,
+    is_paused: AtomicBool,
+    /// Paused walker threads sleep on `resumed` instead of spinning
+    pause_lock: Mutex<()>,
+    resumed: Condvar,
 }
 
 impl ScannerState {
     pub fn new() -> Self {
-        Self { is_cancelled: AtomicBool::new(false) }
+        Self {
+            is_cancelled: AtomicBool::new(false),
+            is_paused: AtomicBool::new(false),
+            pause_lock: Mutex::new(()),
+            resumed: Condvar::new(),
+        }
     }
 
     /// Cancel the scan - uses Release ordering to ensure visibility across threads
     pub fn cancel(&self) {
         self.is_cancelled.store(true, Ordering::Release);
+        // Wake paused threads so they can quit
+        let _guard = self.pause_lock.lock().unwrap();
+        self.resumed.notify_all();
     }
 
     /// Check if scan is cancelled - uses Acquire ordering to synchronize with cancel()
@@ -103,9 +202,30 @@ impl ScannerState {
         self.is_cancelled.load(Ordering::Acquire)
     }
 
-    /// Reset cancellation state - uses Release ordering for next scan
-    pub fn reset(&self) {
-        self.is_cancelled.store(false, Ordering::Release);
+    /// Pause the scan; walker threads block at their next entry
+    pub fn pause(&self) {
+        self.is_paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        let _guard = self.pause_lock.lock().unwrap();
+        self.is_paused.store(false, Ordering::Release);
+        self.resumed.notify_all();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::Acquire)
+    }
+
+    /// Block while the scan is paused; returns false if it was cancelled
+    pub fn wait_if_paused(&self) -> bool {
+        if self.is_paused() {
+            let mut guard = self.pause_lock.lock().unwrap();
+            while self.is_paused() && !self.is_cancelled() {
+                guard = self.resumed.wait(guard).unwrap();
+            }
+        }
+        !self.is_cancelled()
     }
 }
 
@@ -113,6 +233,62 @@ impl Default for ScannerState {
     fn default() -> Self { Self::new() }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IoPriority {
+    #[default]
+    Normal,
+    /// Only touch the disk when nothing else wants it (Linux idle class)
+    Idle,
+}
+
+/// How hard a scan is allowed to lean on the machine
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// Walker threads; None uses one per CPU
+    pub threads: Option<usize>,
+    pub io_priority: IoPriority,
+    /// Nice level for walker threads (0-19); None leaves it unchanged
+    pub nice: Option<i32>,
+    /// Cap on entries (files and directories) visited per second
+    pub max_files_per_sec: Option<u64>,
+}
+
+impl ScanOptions {
+    /// Profile for background work: two idle-IO threads at nice 10, capped
+    /// at 5,000 entries per second
+    pub fn low_impact() -> Self {
+        Self {
+            threads: Some(2),
+            io_priority: IoPriority::Idle,
+            nice: Some(10),
+            max_files_per_sec: Some(5_000),
+        }
+    }
+
+    pub fn validate(&self) -> AppResult<()> {
+        if self.threads.is_some_and(|t| !(1..=256).contains(&t)) {
+            return Err(AppError::invalid("Thread count must be between 1 and 256"));
+        }
+        if self.nice.is_some_and(|n| !(0..=19).contains(&n)) {
+            return Err(AppError::invalid("Nice level must be between 0 and 19"));
+        }
+        if self.max_files_per_sec == Some(0) {
+            return Err(AppError::invalid("Files per second cap must be at least 1"));
+        }
+        Ok(())
+    }
+
+    fn thread_count(&self) -> usize {
+        self.threads.unwrap_or_else(num_cpus::get).max(1)
+    }
+
+    fn lowers_priority(&self) -> bool {
+        self.io_priority == IoPriority::Idle || self.nice.is_some()
+    }
+}
+
 // Lightweight node for parallel collection
 struct TempNode {
     name: String,
@@ -120,26 +296,234 @@ struct TempNode {
     is_dir: bool,
     extension: Option<String>,
     modified_at: Option<u64>,
+    accessed_at: Option<u64>,
+    uid: Option<u32>,
+    gid: Option<u32>,
     children_paths: Vec<PathBuf>,
 }
 
+/// Per-thread walk pacing: lowers the walker thread's priority on first use
+/// and sleeps to stay under the thread's share of the entries-per-second cap
+#[derive(Clone)]
+struct Pacer {
+    caller: std::thread::ThreadId,
+    lower_priority: bool,
+    io_idle: bool,
+    nice: Option<i32>,
+    rate: Option<u64>,
+    prioritized: bool,
+    window_start: Instant,
+    window_count: u64,
+}
+
+impl Pacer {
+    fn new(options: &ScanOptions, num_threads: usize) -> Self {
+        Self {
+            caller: std::thread::current().id(),
+            lower_priority: options.lowers_priority(),
+            io_idle: options.io_priority == IoPriority::Idle,
+            nice: options.nice,
+            // The cap is split evenly so threads can throttle without sharing state
+            rate: options.max_files_per_sec.map(|rate| (rate / num_threads as u64).max(1)),
+            prioritized: false,
+            window_start: Instant::now(),
+            window_count: 0,
+        }
+    }
+
+    /// Call once per visited entry
+    fn visit(&mut self) {
+        // Visitors are built on the calling thread, so priority is
+        // applied on first use from inside each walker thread
+        if !self.prioritized && std::thread::current().id() != self.caller {
+            self.prioritized = true;
+            if self.lower_priority && !platform::lower_thread_priority(self.io_idle, self.nice) {
+                warn!("could not lower walker thread priority");
+            }
+        }
+
+        if let Some(rate) = self.rate {
+            self.window_count += 1;
+            if self.window_count > rate {
+                let elapsed = self.window_start.elapsed();
+                if elapsed < Duration::from_secs(1) {
+                    std::thread::sleep(Duration::from_secs(1) - elapsed);
+                }
+                self.window_start = Instant::now();
+                self.window_count = 1;
+            }
+        }
+    }
+}
+
+/// Size of a directory as a scan of it would record it
+#[derive(Debug, Clone, Copy)]
+pub struct MeasuredSize {
+    pub bytes: u64,
+    /// False when the walk stopped at the node cap; `bytes` is then a lower bound
+    pub complete: bool,
+}
+
 pub struct Scanner {
     state: Arc<ScannerState>,
+    /// Scan job this scanner reports progress for
+    job_id: u64,
+    options: ScanOptions,
+    estimate: Option<ScanEstimate>,
+    /// Paths skipped by the walk, with the root they're relative to (the
+    /// scanned roots when None)
+    excludes: Option<(Arc<PathPatterns>, Option<PathBuf>)>,
 }
 
 impl Scanner {
     pub fn new(state: Arc<ScannerState>) -> Self {
-        Self { state }
+        Self { state, job_id: 0, options: ScanOptions::default(), estimate: None, excludes: None }
+    }
+
+    pub fn with_job_id(mut self, job_id: u64) -> Self {
+        self.job_id = job_id;
+        self
     }
 
-    pub fn scan(&self, root_path: &Path, app_handle: Option<&AppHandle>) -> Option<FileNode> {
-        self.state.reset();
+    pub fn with_options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
 
+    /// Expected size of the scan, used for percent and ETA
+    pub fn with_estimate(mut self, estimate: Option<ScanEstimate>) -> Self {
+        // The walk never visits more than the node cap
+        self.estimate = estimate.map(|e| ScanEstimate {
+            entries: e.entries.min(MAX_SCANNED_NODES as u64),
+            ..e
+        });
+        self
+    }
+
+    /// Skip paths matching `excludes`. They are matched relative to `base`
+    /// when given (a subtree rescan passes the scan root), otherwise relative
+    /// to the scanned roots.
+    pub fn with_excludes(mut self, excludes: Arc<PathPatterns>, base: Option<PathBuf>) -> Self {
+        if !excludes.is_empty() {
+            self.excludes = Some((excludes, base));
+        }
+        self
+    }
+
+    pub fn scan(&self, root_path: &Path, app_handle: Option<&AppHandle>) -> Option<ScanResult> {
+        self.run(root_path, &[root_path.to_path_buf()], None, app_handle)
+    }
+
+    /// Scan several roots in one walk (sharing hard link dedup) into a tree
+    /// under a synthetic root named after the workspace
+    pub fn scan_workspace(
+        &self,
+        name: &str,
+        roots: &[PathBuf],
+        app_handle: Option<&AppHandle>,
+    ) -> Option<ScanResult> {
+        let key = PathBuf::from(workspace::workspace_key(name));
+        self.run(&key, roots, Some(name), app_handle)
+    }
+
+    /// Total the bytes under `dir` the way a scan would (same options and
+    /// excludes, hard links counted once) without building an index or tree.
+    /// Returns None when cancelled.
+    pub fn measure(&self, dir: &Path) -> Option<MeasuredSize> {
+        let num_threads = self.options.thread_count();
+        let walker = self.walk_builder(&[dir.to_path_buf()], num_threads)?.build_parallel();
+        let pacer = Pacer::new(&self.options, num_threads);
+        let entries = AtomicU64::new(0);
+        let bytes = AtomicU64::new(0);
+        let truncated = AtomicBool::new(false);
+        let seen_inodes: DashSet<(u64, u64)> = DashSet::new();
+
+        walker.run(|| {
+            let mut pacer = pacer.clone();
+            let (state, entries, bytes, truncated, seen) =
+                (&self.state, &entries, &bytes, &truncated, &seen_inodes);
+            Box::new(move |entry| {
+                pacer.visit();
+                if !state.wait_if_paused() {
+                    return WalkState::Quit;
+                }
+                // Past the cap the scan's own total was truncated too
+                if entries.fetch_add(1, Ordering::Relaxed) >= MAX_SCANNED_NODES as u64 {
+                    truncated.store(true, Ordering::Relaxed);
+                    return WalkState::Quit;
+                }
+
+                let Ok(entry) = entry else { return WalkState::Continue };
+                if entry.file_type().is_some_and(|t| t.is_dir()) {
+                    return WalkState::Continue;
+                }
+                if let Ok(meta) = entry.metadata() {
+                    if seen.insert((meta.dev(), meta.ino())) {
+                        bytes.fetch_add(meta.len(), Ordering::Relaxed);
+                    }
+                }
+                WalkState::Continue
+            })
+        });
+
+        if self.state.is_cancelled() {
+            return None;
+        }
+        Some(MeasuredSize {
+            bytes: bytes.load(Ordering::Relaxed),
+            complete: !truncated.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Walker over `roots` that skips excluded paths (None without roots)
+    fn walk_builder(&self, roots: &[PathBuf], num_threads: usize) -> Option<WalkBuilder> {
+        let (first_root, other_roots) = roots.split_first()?;
+        let mut builder = WalkBuilder::new(first_root);
+        for root in other_roots {
+            builder.add(root);
+        }
+        builder
+            .hidden(false)           // Include hidden files
+            .ignore(false)           // Don't respect .gitignore
+            .git_ignore(false)       // Don't respect .gitignore
+            .git_global(false)       // Don't respect global gitignore
+            .git_exclude(false)      // Don't respect .git/info/exclude
+            .follow_links(false)     // Don't follow symlinks
+            .threads(num_threads);
+        if let Some((excludes, base)) = self.excludes.clone() {
+            let bases = match base {
+                Some(base) => vec![base],
+                None => roots.to_vec(),
+            };
+            // Pruning a directory here skips everything below it
+            builder.filter_entry(move |entry| {
+                let path = entry.path();
+                !bases
+                    .iter()
+                    .filter(|base| path.starts_with(base))
+                    .any(|base| excludes.matches_entry(base, path))
+            });
+        }
+        Some(builder)
+    }
+
+    /// `root_path` is the path of the result's root: the scanned directory
+    /// itself, or the workspace key
+    fn run(
+        &self,
+        root_path: &Path,
+        roots: &[PathBuf],
+        workspace_name: Option<&str>,
+        app_handle: Option<&AppHandle>,
+    ) -> Option<ScanResult> {
+        let job_id = self.job_id;
+        let estimate = self.estimate;
+        let estimate_source = estimate.map(|e| e.source);
+        let num_threads = self.options.thread_count();
+        let builder = self.walk_builder(roots, num_threads)?;
         let total_start = Instant::now();
-        println!("\n{}", "=".repeat(60));
-        println!("[SpaceView] Starting scan: {:?}", root_path);
-        println!("[SpaceView] Threads: {}", num_cpus::get());
-        println!("{}", "=".repeat(60));
+        let _scan_span = info_span!("scan", job_id, path = %root_path.display()).entered();
+        info!(threads = num_threads, roots = ?roots, "scan started");
 
         let scanned_files = Arc::new(AtomicU64::new(0));
         let scanned_dirs = Arc::new(AtomicU64::new(0));
@@ -159,42 +543,84 @@ impl Scanner {
 
         let app_handle = app_handle.cloned();
 
+        // Sizes aggregated per child of the root (only when emitting events)
+        let top_level: Option<Arc<DashMap<OsString, TopLevelStat>>> =
+            app_handle.as_ref().map(|_| Arc::new(DashMap::new()));
+
         // Spawn progress reporter thread (only when emitting events)
         if let Some(app) = app_handle.clone() {
-            let cancel_flag = self.state.clone();
+            let scan_state = self.state.clone();
+            let top_level = top_level.clone();
+            let root = root_path.to_path_buf();
             std::thread::spawn(move || {
+                let started = Instant::now();
                 let mut last_emit = std::time::Instant::now();
-                while let Ok((files, dirs, size, path)) = progress_rx.recv() {
-                    if cancel_flag.is_cancelled() { break; }
-                    if last_emit.elapsed().as_millis() >= 50 {
+                let mut last_partial = Instant::now();
+                let mut last = (0, 0, 0, String::new());
+                let mut reported_paused = false;
+                loop {
+                    // Wake up periodically so pause/resume is reported even
+                    // though paused walkers send nothing
+                    let fresh = match progress_rx.recv_timeout(PROGRESS_POLL) {
+                        Ok(sample) => {
+                            last = sample;
+                            true
+                        }
+                        Err(RecvTimeoutError::Timeout) => false,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
+                    if scan_state.is_cancelled() { break; }
+                    let paused = scan_state.is_paused();
+                    if paused != reported_paused || (fresh && last_emit.elapsed().as_millis() >= 50) {
+                        let (files, dirs, size, ref path) = last;
+                        let entries = files + dirs;
+                        let secs = started.elapsed().as_secs_f64();
+                        let entries_per_sec = (entries as f64 / secs.max(1e-3)) as u64;
+                        let phase_progress = estimate
+                            .map(|e| (entries as f64 / e.entries.max(1) as f64).min(0.99));
+                        let eta_ms = match estimate {
+                            Some(e) if !paused && entries_per_sec > 0 && entries < e.entries => {
+                                // Rest of the walk, plus the later phases' share of the total
+                                let walk_left = (e.entries - entries) as f64 / entries_per_sec as f64;
+                                let tail = (secs + walk_left) * (1.0 / WALK_SHARE - 1.0);
+                                Some(((walk_left + tail) * 1000.0) as u64)
+                            }
+                            _ => None,
+                        };
                         let _ = app.emit("scan-progress", ScanProgress {
+                            job_id,
                             scanned_files: files,
                             scanned_dirs: dirs,
-                            current_path: path,
+                            current_path: path.clone(),
                             total_size: size,
                             is_complete: false,
+                            is_paused: paused,
                             phase: "walking".to_string(),
+                            percent: phase_progress.map(|p| p * WALK_SHARE * 100.0),
+                            phase_progress,
+                            eta_ms,
+                            entries_per_sec,
+                            estimate_source,
                         });
+                        reported_paused = paused;
                         last_emit = std::time::Instant::now();
                     }
+                    if let Some(top_level) = top_level.as_ref() {
+                        if !paused && last_partial.elapsed() >= PARTIAL_INTERVAL {
+                            let partial = partial_scan(job_id, &root, top_level, last.2);
+                            let _ = app.emit("scan-partial", partial);
+                            last_partial = Instant::now();
+                        }
+                    }
                 }
             });
         }
 
         // Phase 1: Parallel directory walk with work-stealing
+        let walk_span = info_span!("scan_phase", phase = "walking").entered();
         let walk_start = Instant::now();
-        println!("[Phase 1] Starting parallel walk...");
 
-        let num_threads = num_cpus::get();
-        let walker = WalkBuilder::new(root_path)
-            .hidden(false)           // Include hidden files
-            .ignore(false)           // Don't respect .gitignore
-            .git_ignore(false)       // Don't respect .gitignore
-            .git_global(false)       // Don't respect global gitignore
-            .git_exclude(false)      // Don't respect .git/info/exclude
-            .follow_links(false)     // Don't follow symlinks
-            .threads(num_threads)
-            .build_parallel();
+        let walker = builder.build_parallel();
 
         let nodes_clone = nodes.clone();
         let files_clone = scanned_files.clone();
@@ -203,6 +629,9 @@ impl Scanner {
         let cancel_clone = self.state.clone();
         let progress_tx_clone = progress_tx.clone();
         let seen_inodes_clone = seen_inodes.clone();
+        let pacer = Pacer::new(&self.options, num_threads);
+        let top_level_clone = top_level.clone();
+        let roots_buf = roots.to_vec();
 
         // Parallel walk with work-stealing + lock-free DashMap
         walker.run(|| {
@@ -214,10 +643,14 @@ impl Scanner {
             let tx = progress_tx_clone.clone();
             let seen = seen_inodes_clone.clone();
             let limit_hit = hard_limit_hit.clone();
+            let top_level = top_level_clone.clone();
+            let roots = roots_buf.clone();
             let mut counter: u64 = 0;
+            let mut pacer = pacer.clone();
 
             Box::new(move |entry| {
-                if cancel.is_cancelled() {
+                pacer.visit();
+                if !cancel.wait_if_paused() {
                     return WalkState::Quit;
                 }
 
@@ -235,18 +668,22 @@ impl Scanner {
                 // Use file_type() - comes from readdir, no extra syscall
                 let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
 
-                // Get metadata for inode tracking, size, and modification time
+                // Get metadata for inode tracking, size, timestamps, and ownership
                 let metadata = entry.metadata();
-                let (file_size, inode_key, modified_at) = if let Ok(ref meta) = metadata {
+                let (file_size, inode_key, modified_at, accessed_at, owner) = if let Ok(ref meta) = metadata {
                     let dev = meta.dev();
                     let ino = meta.ino();
                     let size = if is_dir { 0 } else { meta.len() };
                     let mtime = meta.modified().ok()
                         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                         .map(|d| d.as_secs());
-                    (size, Some((dev, ino)), mtime)
+                    // Not every platform/mount records atime
+                    let atime = meta.accessed().ok()
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_secs());
+                    (size, Some((dev, ino)), mtime, atime, Some((meta.uid(), meta.gid())))
                 } else {
-                    (0, None, None)
+                    (0, None, None, None, None)
                 };
 
                 // Check for hard links (same file with multiple paths)
@@ -283,6 +720,10 @@ impl Scanner {
                 // For duplicate hard links, store 0 size to avoid double-counting in tree
                 let stored_size = if is_duplicate { 0 } else { file_size };
 
+                if let Some(top_level) = top_level.as_ref() {
+                    record_top_level(top_level, &roots, &path, is_dir, stored_size);
+                }
+
                 // DashMap insert is lock-free!
                 nodes.insert(path.clone(), TempNode {
                     name,
@@ -290,6 +731,9 @@ impl Scanner {
                     is_dir,
                     extension,
                     modified_at,
+                    accessed_at,
+                    uid: owner.map(|(uid, _)| uid),
+                    gid: owner.map(|(_, gid)| gid),
                     children_paths: Vec::new(),
                 });
 
@@ -318,36 +762,45 @@ impl Scanner {
         let nodes_count = nodes.len();
         let unique_inodes = seen_inodes.len();
         let hard_link_duplicates = files_count.saturating_sub(unique_inodes as u64);
-
-        println!("[Phase 1] Walk completed in {:?}", walk_time);
-        println!("          Files: {}, Dirs: {}, Total: {}",
-            files_count, dirs_count, nodes_count);
-        println!("          Unique file inodes: {}", unique_inodes);
-        if hard_link_duplicates > 0 {
-            println!("          Hard link duplicates: {} (size not counted twice)", hard_link_duplicates);
-        }
-        println!("          Speed: {:.0} files/sec",
-            files_count as f64 / walk_time.as_secs_f64());
-        println!("          Size: {:.2} GB (deduplicated)", size_total as f64 / 1_073_741_824.0);
+        let walk_rate = ((files_count + dirs_count) as f64 / walk_time.as_secs_f64().max(1e-3)) as u64;
+
+        info!(
+            files = files_count,
+            dirs = dirs_count,
+            nodes = nodes_count,
+            unique_inodes,
+            hard_link_duplicates,
+            entries_per_sec = walk_rate,
+            bytes = size_total,
+            "walk complete"
+        );
         if hard_limit_hit.load(Ordering::Relaxed) {
-            println!("          NOTE: Node cap ({}) reached; scan truncated to protect memory", MAX_SCANNED_NODES);
+            warn!(cap = MAX_SCANNED_NODES, "node cap reached; scan truncated to protect memory");
         }
+        drop(walk_span);
 
-        if self.state.is_cancelled() { return None; }
+        // A pause that lands after the walk holds the scan between phases
+        if !self.state.wait_if_paused() { return None; }
 
         // Phase 2: Build parent-child relationships (parallel with DashMap)
         if let Some(app) = app_handle.as_ref() {
             let _ = app.emit("scan-progress", ScanProgress {
+                job_id,
                 scanned_files: files_count,
                 scanned_dirs: dirs_count,
                 current_path: "Building relationships...".to_string(),
                 total_size: size_total,
                 is_complete: false,
+                is_paused: false,
                 phase: "relations".to_string(),
+                percent: Some(90.0),
+                phase_progress: Some(0.0),
+                eta_ms: None,
+                entries_per_sec: walk_rate,
+                estimate_source,
             });
         }
-        let relation_start = Instant::now();
-        println!("[Phase 2] Building parent-child relationships...");
+        let relation_span = info_span!("scan_phase", phase = "relations").entered();
         {
             let all_paths: Vec<PathBuf> = nodes.iter().map(|r| r.key().clone()).collect();
             for path in all_paths {
@@ -361,101 +814,114 @@ impl Scanner {
                 }
             }
         }
-        let relation_time = relation_start.elapsed();
-        println!("[Phase 2] Relationships built in {:?}", relation_time);
+        debug!("relationships built");
+        drop(relation_span);
+
+        if !self.state.wait_if_paused() { return None; }
 
         // Phase 3: Calculate sizes bottom-up
         if let Some(app) = app_handle.as_ref() {
             let _ = app.emit("scan-progress", ScanProgress {
+                job_id,
                 scanned_files: files_count,
                 scanned_dirs: dirs_count,
                 current_path: "Calculating sizes...".to_string(),
                 total_size: size_total,
                 is_complete: false,
+                is_paused: false,
                 phase: "sizes".to_string(),
+                percent: Some(93.0),
+                phase_progress: Some(0.0),
+                eta_ms: None,
+                entries_per_sec: walk_rate,
+                estimate_source,
             });
         }
-        let size_start = Instant::now();
-        println!("[Phase 3] Calculating directory sizes (bottom-up)...");
-        self.calc_sizes_bottomup_dashmap(&nodes, root_path);
-        let size_time = size_start.elapsed();
-        println!("[Phase 3] Size calculation completed in {:?}", size_time);
+        let size_span = info_span!("scan_phase", phase = "sizes").entered();
+        for root in roots {
+            self.calc_sizes_bottomup_dashmap(&nodes, root);
+        }
+        debug!("directory sizes calculated");
+        drop(size_span);
+
+        if !self.state.wait_if_paused() { return None; }
 
         // Phase 4: Build final tree
         if let Some(app) = app_handle.as_ref() {
             let _ = app.emit("scan-progress", ScanProgress {
+                job_id,
                 scanned_files: files_count,
                 scanned_dirs: dirs_count,
                 current_path: "Building tree...".to_string(),
                 total_size: size_total,
                 is_complete: false,
+                is_paused: false,
                 phase: "tree".to_string(),
+                percent: Some(96.0),
+                phase_progress: Some(0.0),
+                eta_ms: None,
+                entries_per_sec: walk_rate,
+                estimate_source,
             });
         }
-        let tree_start = Instant::now();
-        println!("[Phase 4] Building output tree (depth={}, max_nodes={})...", MAX_DEPTH, MAX_TOTAL_NODES);
-        let tree_node_count = AtomicU64::new(0);
-        let tree = self.build_tree_dashmap(&nodes, root_path, 0, &tree_node_count);
-        let final_node_count = tree_node_count.load(Ordering::Relaxed);
-        let tree_time = tree_start.elapsed();
-        println!("[Phase 4] Tree built in {:?} ({} nodes for UI)", tree_time, final_node_count);
-
-        // Final summary
-        let total_time = total_start.elapsed();
-        let memory_bytes = get_memory_usage();
-        let memory_mb = memory_bytes as f64 / 1_048_576.0;
-
-        println!("{}", "=".repeat(60));
-        println!("[SpaceView] SCAN COMPLETE");
-        println!("{}", "-".repeat(60));
-        println!("  Total time:     {:?}", total_time);
-        println!("  Phase 1 (walk): {:?} ({:.1}%)", walk_time,
-            walk_time.as_secs_f64() / total_time.as_secs_f64() * 100.0);
-        println!("  Phase 2 (rel):  {:?} ({:.1}%)", relation_time,
-            relation_time.as_secs_f64() / total_time.as_secs_f64() * 100.0);
-        println!("  Phase 3 (size): {:?} ({:.1}%)", size_time,
-            size_time.as_secs_f64() / total_time.as_secs_f64() * 100.0);
-        println!("  Phase 4 (tree): {:?} ({:.1}%)", tree_time,
-            tree_time.as_secs_f64() / total_time.as_secs_f64() * 100.0);
-        println!("{}", "-".repeat(60));
-        println!("  Files:          {}", files_count);
-        println!("  Directories:    {}", dirs_count);
-        println!("  Total nodes:    {}", nodes_count);
-        println!("  Total size:     {:.2} GB", size_total as f64 / 1_073_741_824.0);
-        println!("  Throughput:     {:.0} files/sec", files_count as f64 / total_time.as_secs_f64());
-        println!("{}", "-".repeat(60));
-        println!("  Memory used:    {:.1} MB", memory_mb);
-        println!("  Per-node mem:   {:.0} bytes/node", memory_bytes as f64 / nodes_count as f64);
-        println!("{}", "=".repeat(60));
+        let tree_span = info_span!("scan_phase", phase = "tree").entered();
+        let index = match workspace_name {
+            Some(name) => self.build_workspace_index(&nodes, root_path, name, roots),
+            None => self.build_index_dashmap(&nodes, root_path),
+        };
+        let mut final_node_count = 0;
+        let tree = index.to_file_node(ROOT_ID, MAX_DEPTH, MAX_TOTAL_NODES, &mut final_node_count);
+        let extension_stats = analysis::extension_stats(&index, ROOT_ID);
+        debug!(ui_nodes = final_node_count, indexed = index.len(), "tree built");
+        drop(tree_span);
 
         // Emit metrics event for UI
+        let total_time = total_start.elapsed();
+        let phases = logging::phase_timings(job_id);
         let metrics = ScanMetrics {
+            job_id,
             total_time_ms: total_time.as_millis() as u64,
-            walk_time_ms: walk_time.as_millis() as u64,
-            relation_time_ms: relation_time.as_millis() as u64,
-            size_calc_time_ms: size_time.as_millis() as u64,
-            tree_build_time_ms: tree_time.as_millis() as u64,
+            walk_time_ms: phases.get("walking").as_millis() as u64,
+            relation_time_ms: phases.get("relations").as_millis() as u64,
+            size_calc_time_ms: phases.get("sizes").as_millis() as u64,
+            tree_build_time_ms: phases.get("tree").as_millis() as u64,
             total_files: files_count,
             total_dirs: dirs_count,
             total_size: size_total,
             files_per_sec: (files_count as f64 / total_time.as_secs_f64()) as u64,
             nodes_in_map: nodes_count,
-            memory_used_mb: memory_mb,
+            memory_used_mb: get_memory_usage() as f64 / 1_048_576.0,
         };
+        info!(
+            total_ms = metrics.total_time_ms,
+            walk_ms = metrics.walk_time_ms,
+            relations_ms = metrics.relation_time_ms,
+            sizes_ms = metrics.size_calc_time_ms,
+            tree_ms = metrics.tree_build_time_ms,
+            memory_mb = format_args!("{:.1}", metrics.memory_used_mb),
+            "scan complete"
+        );
         if let Some(app) = app_handle.as_ref() {
             let _ = app.emit("scan-metrics", metrics);
 
             let _ = app.emit("scan-progress", ScanProgress {
+                job_id,
                 scanned_files: files_count,
                 scanned_dirs: dirs_count,
                 current_path: String::new(),
                 total_size: size_total,
                 is_complete: true,
+                is_paused: false,
                 phase: "complete".to_string(),
+                percent: Some(100.0),
+                phase_progress: Some(1.0),
+                eta_ms: Some(0),
+                entries_per_sec: walk_rate,
+                estimate_source,
             });
         }
 
-        tree
+        tree.map(|root| ScanResult { root, index, extension_stats })
     }
 
     fn calc_sizes_bottomup_dashmap(&self, nodes: &Arc<DashMap<PathBuf, TempNode>>, root: &Path) {
@@ -493,144 +959,138 @@ impl Scanner {
         }
     }
 
-    fn count_subtree(&self, nodes: &Arc<DashMap<PathBuf, TempNode>>, path: &Path) -> (u64, u64) {
-        let mut files = 0u64;
-        let mut dirs = 0u64;
-        let mut stack = vec![path.to_path_buf()];
-
-        while let Some(p) = stack.pop() {
-            if let Some(node) = nodes.get(&p) {
-                if node.is_dir {
-                    dirs += 1;
-                    for child in &node.children_paths {
-                        stack.push(child.clone());
-                    }
-                } else {
-                    files += 1;
-                }
-            }
-        }
-
-        (files, dirs)
+    /// Flatten every walked node into the index (no depth or node caps)
+    fn build_index_dashmap(&self, nodes: &Arc<DashMap<PathBuf, TempNode>>, root: &Path) -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(root, nodes.len());
+        self.insert_walked(&mut index, nodes, root, None, None);
+        index.recompute_totals();
+        index
     }
 
-    fn build_tree_dashmap(&self, nodes: &Arc<DashMap<PathBuf, TempNode>>, path: &Path, depth: usize, node_count: &AtomicU64) -> Option<FileNode> {
-        // Check if we've hit the total node limit
-        if node_count.load(Ordering::Relaxed) >= MAX_TOTAL_NODES as u64 {
-            return None;
+    /// Hang each root's subtree, named by its absolute path, under a
+    /// synthetic root named after the workspace
+    fn build_workspace_index(
+        &self,
+        nodes: &Arc<DashMap<PathBuf, TempNode>>,
+        key: &Path,
+        name: &str,
+        roots: &[PathBuf],
+    ) -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(key, nodes.len() + 1);
+        let root_id = index.insert(None, IndexNode {
+            name: name.to_string(),
+            is_dir: true,
+            ..Default::default()
+        });
+        for root in roots {
+            let root_name = root.to_string_lossy().to_string();
+            self.insert_walked(&mut index, nodes, root, Some(root_id), Some(root_name));
         }
+        index.recompute_totals();
+        index
+    }
 
-        let node = nodes.get(path)?;
-        let path_str = path.to_string_lossy().to_string();
-
-        // Increment node count
-        node_count.fetch_add(1, Ordering::Relaxed);
-
-        if !node.is_dir {
-            return Some(FileNode {
-                id: path_str.clone(),
-                name: node.name.clone(),
-                path: path_str,
+    /// Insert the walked subtree at `top` under `parent`, optionally renaming
+    /// its top node
+    fn insert_walked(
+        &self,
+        index: &mut ScanIndex,
+        nodes: &Arc<DashMap<PathBuf, TempNode>>,
+        top: &Path,
+        parent: Option<NodeId>,
+        mut top_name: Option<String>,
+    ) {
+        let mut stack: Vec<(PathBuf, Option<NodeId>)> = vec![(top.to_path_buf(), parent)];
+
+        while let Some((path, parent)) = stack.pop() {
+            let Some(node) = nodes.get(&path) else { continue };
+            let id = index.insert(parent, IndexNode {
+                // Only the first node popped is `top`
+                name: top_name.take().unwrap_or_else(|| node.name.clone()),
                 size: node.size,
-                is_dir: false,
-                children: vec![],
+                is_dir: node.is_dir,
                 extension: node.extension.clone(),
-                file_count: 0,
-                dir_count: 0,
                 modified_at: node.modified_at,
+                accessed_at: node.accessed_at,
+                uid: node.uid,
+                gid: node.gid,
+                ..Default::default()
             });
+            // Pushed in reverse name order so siblings are inserted sorted
+            let mut children = node.children_paths.clone();
+            children.sort_unstable_by(|a, b| b.file_name().cmp(&a.file_name()));
+            for child in children {
+                stack.push((child, Some(id)));
+            }
         }
+    }
+}
 
-        // Sort children by size (descending)
-        let children_paths = node.children_paths.clone();
-        drop(node); // Release lock for recursive calls
+/// The top-level entry `path` counts towards, and whether it is that entry:
+/// a child of the root, or for workspaces the root it was found under
+fn top_level_key<'a>(roots: &'a [PathBuf], path: &'a Path) -> Option<(&'a OsStr, bool)> {
+    if let [root] = roots {
+        let rel = path.strip_prefix(root).ok()?;
+        let mut components = rel.components();
+        let first = components.next()?;
+        return Some((first.as_os_str(), components.next().is_none()));
+    }
+    let root = roots.iter().find(|r| path.starts_with(r))?;
+    Some((root.as_os_str(), path == root))
+}
 
-        let mut children_sorted: Vec<PathBuf> = children_paths;
-        children_sorted.sort_by(|a, b| {
-            let size_a = nodes.get(a).map(|n| n.size).unwrap_or(0);
-            let size_b = nodes.get(b).map(|n| n.size).unwrap_or(0);
-            size_b.cmp(&size_a)
-        });
+/// Add one walked entry to the totals of the top-level entry it lives under
+fn record_top_level(
+    top_level: &DashMap<OsString, TopLevelStat>,
+    roots: &[PathBuf],
+    path: &Path,
+    is_dir: bool,
+    size: u64,
+) {
+    let Some((key, is_child_itself)) = top_level_key(roots, path) else { return };
+
+    let mut stat = match top_level.get_mut(key) {
+        Some(stat) => stat,
+        None => top_level.entry(key.to_os_string()).or_default(),
+    };
+    // Anything below the child means the child is a directory
+    stat.is_dir |= is_dir || !is_child_itself;
+    if !is_dir {
+        stat.file_count += 1;
+        stat.size += size;
+    } else if !is_child_itself {
+        stat.dir_count += 1;
+    }
+}
 
-        let mut children = Vec::new();
-        let mut other_size: u64 = 0;
-        let mut other_file_count: u64 = 0;
-        let mut other_dir_count: u64 = 0;
-
-        for child_path in children_sorted.iter() {
-            // Check total node limit before recursing
-            let at_limit = node_count.load(Ordering::Relaxed) >= MAX_TOTAL_NODES as u64;
-
-            if depth < MAX_DEPTH && !at_limit {
-                if let Some(child) = self.build_tree_dashmap(nodes, child_path, depth + 1, node_count) {
-                    children.push(child);
-                } else if let Some(cn) = nodes.get(child_path) {
-                    // Node was skipped due to limit, count as "other"
-                    other_size += cn.size;
-                    let (files, dirs) = self.count_subtree(nodes, child_path);
-                    other_file_count += files;
-                    other_dir_count += dirs;
-                }
-            } else {
-                if let Some(cn) = nodes.get(child_path) {
-                    other_size += cn.size;
-                    let (files, dirs) = self.count_subtree(nodes, child_path);
-                    other_file_count += files;
-                    other_dir_count += dirs;
-                }
+/// Snapshot the per-child totals, largest first
+fn partial_scan(
+    job_id: u64,
+    root: &Path,
+    top_level: &DashMap<OsString, TopLevelStat>,
+    total_size: u64,
+) -> PartialScan {
+    let mut children: Vec<PartialEntry> = top_level
+        .iter()
+        .map(|entry| {
+            let stat = entry.value();
+            PartialEntry {
+                name: entry.key().to_string_lossy().to_string(),
+                path: root.join(entry.key()).to_string_lossy().to_string(),
+                size: stat.size,
+                is_dir: stat.is_dir,
+                file_count: stat.file_count,
+                dir_count: stat.dir_count,
             }
-        }
-
-        if other_file_count + other_dir_count > 0 {
-            children.push(FileNode {
-                id: format!("{}/__other__", path_str),
-                name: format!("<{} more items>", other_file_count + other_dir_count),
-                path: path_str.clone(),
-                size: other_size,
-                is_dir: true,
-                children: vec![],
-                extension: None,
-                file_count: other_file_count,
-                dir_count: other_dir_count,
-                modified_at: None,
-            });
-        }
-
-        let file_count: u64 = children.iter()
-            .map(|c| {
-                if c.is_dir && c.children.is_empty() && c.name.starts_with("<") {
-                    c.file_count
-                } else if c.is_dir {
-                    c.file_count
-                } else {
-                    1
-                }
-            })
-            .sum();
-        let dir_count: u64 = children.iter()
-            .map(|c| {
-                if c.is_dir && c.children.is_empty() && c.name.starts_with("<") {
-                    c.dir_count
-                } else if c.is_dir {
-                    1 + c.dir_count
-                } else {
-                    0
-                }
-            })
-            .sum();
-
-        let node = nodes.get(&path.to_path_buf())?;
-        Some(FileNode {
-            id: path_str.clone(),
-            name: node.name.clone(),
-            path: path_str,
-            size: node.size,
-            is_dir: true,
-            children,
-            extension: None,
-            file_count,
-            dir_count,
-            modified_at: node.modified_at,
         })
+        .collect();
+    children.sort_unstable_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    children.truncate(PARTIAL_MAX_CHILDREN);
+
+    PartialScan {
+        job_id,
+        root_path: root.to_string_lossy().to_string(),
+        total_size,
+        children,
     }
 }
@@ -0,0 +1,410 @@
+//! Backend search over the full scan index.
+//!
+//! Matches names by substring, glob, or regex and filters by size, mtime,
+//! extension, and category. Results are ordered by size (largest first) and
+//! returned one page at a time so huge result sets never cross the IPC
+//! boundary in one piece.
+
+use crate::category::{normalize_extension, CategoryTable, FileCategory};
+use crate::error::{AppError, AppResult};
+use crate::index::{IndexNode, NodeId, ScanIndex};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_PAGE_SIZE: usize = 200;
+const MAX_PAGE_SIZE: usize = 5_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternMode {
+    #[default]
+    Substring,
+    Glob,
+    Regex,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchQuery {
+    /// Scan root or subtree to search (defaults to the current scan root)
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub mode: PatternMode,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    /// Only match entries modified before this unix timestamp (seconds)
+    #[serde(default)]
+    pub modified_before: Option<u64>,
+    /// Only match entries modified after this unix timestamp (seconds)
+    #[serde(default)]
+    pub modified_after: Option<u64>,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<FileCategory>,
+    #[serde(default)]
+    pub include_dirs: bool,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+    pub extension: Option<String>,
+    pub modified_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResults {
+    pub total_matches: usize,
+    pub offset: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+enum NameMatcher {
+    Any,
+    Substring { needle: String, case_sensitive: bool },
+    Glob { matcher: GlobMatcher, full_path: bool },
+    Regex(Regex),
+}
+
+impl NameMatcher {
+    fn compile(query: &SearchQuery) -> AppResult<Self> {
+        let Some(pattern) = query.pattern.as_deref().filter(|p| !p.is_empty()) else {
+            return Ok(NameMatcher::Any);
+        };
+
+        match query.mode {
+            PatternMode::Substring => Ok(NameMatcher::Substring {
+                needle: if query.case_sensitive {
+                    pattern.to_string()
+                } else {
+                    pattern.to_lowercase()
+                },
+                case_sensitive: query.case_sensitive,
+            }),
+            PatternMode::Glob => {
+                let glob = GlobBuilder::new(pattern)
+                    .case_insensitive(!query.case_sensitive)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| AppError::invalid(format!("Invalid glob pattern: {}", e)))?;
+                Ok(NameMatcher::Glob {
+                    matcher: glob.compile_matcher(),
+                    // Patterns with a separator match against the full path
+                    full_path: pattern.contains('/'),
+                })
+            }
+            PatternMode::Regex => {
+                let regex = RegexBuilder::new(pattern)
+                    .case_insensitive(!query.case_sensitive)
+                    .build()
+                    .map_err(|e| AppError::invalid(format!("Invalid regex: {}", e)))?;
+                Ok(NameMatcher::Regex(regex))
+            }
+        }
+    }
+
+    fn is_match(&self, index: &ScanIndex, id: NodeId, node: &IndexNode) -> bool {
+        match self {
+            NameMatcher::Any => true,
+            NameMatcher::Substring { needle, case_sensitive } => {
+                if *case_sensitive {
+                    node.name.contains(needle.as_str())
+                } else {
+                    node.name.to_lowercase().contains(needle.as_str())
+                }
+            }
+            NameMatcher::Glob { matcher, full_path } => {
+                if *full_path {
+                    matcher.is_match(index.path_of(id))
+                } else {
+                    matcher.is_match(&node.name)
+                }
+            }
+            NameMatcher::Regex(regex) => regex.is_match(&node.name),
+        }
+    }
+}
+
+fn matches_filters(
+    query: &SearchQuery,
+    extensions: &[String],
+    categories: &CategoryTable,
+    node: &IndexNode,
+) -> bool {
+    if node.is_dir && !query.include_dirs {
+        return false;
+    }
+    if query.min_size.is_some_and(|min| node.size < min) {
+        return false;
+    }
+    if query.max_size.is_some_and(|max| node.size > max) {
+        return false;
+    }
+    if let Some(before) = query.modified_before {
+        if node.modified_at.is_none_or(|m| m >= before) {
+            return false;
+        }
+    }
+    if let Some(after) = query.modified_after {
+        if node.modified_at.is_none_or(|m| m <= after) {
+            return false;
+        }
+    }
+    if !extensions.is_empty() {
+        let Some(ext) = node.extension.as_deref() else { return false };
+        if !extensions.iter().any(|e| e == ext) {
+            return false;
+        }
+    }
+    if !query.categories.is_empty() {
+        if node.is_dir {
+            return false;
+        }
+        let category = categories.category_of(node.extension.as_deref());
+        if !query.categories.contains(&category) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Run a search against an index, returning one page sorted by size
+pub fn search_index(
+    index: &ScanIndex,
+    query: &SearchQuery,
+    categories: &CategoryTable,
+) -> AppResult<SearchResults> {
+    let matcher = NameMatcher::compile(query)?;
+    let extensions: Vec<String> = query.extensions.iter().map(|e| normalize_extension(e)).collect();
+
+    let start = index.resolve(query.path.as_deref())?;
+
+    let mut matches: Vec<(u64, NodeId)> = index
+        .descendants(start)
+        .filter_map(|id| {
+            let node = index.get(id)?;
+            (matches_filters(query, &extensions, categories, node) && matcher.is_match(index, id, node))
+                .then_some((node.size, id))
+        })
+        .collect();
+
+    matches.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let hits = matches
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .filter_map(|&(_, id)| {
+            let node = index.get(id)?;
+            Some(SearchHit {
+                path: index.path_of(id).to_string_lossy().to_string(),
+                name: node.name.clone(),
+                size: node.size,
+                is_dir: node.is_dir,
+                extension: node.extension.clone(),
+                modified_at: node.modified_at,
+            })
+        })
+        .collect();
+
+    Ok(SearchResults {
+        total_matches: matches.len(),
+        offset,
+        hits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn file(name: &str, size: u64, modified_at: u64) -> IndexNode {
+        IndexNode {
+            name: name.to_string(),
+            size,
+            extension: Path::new(name)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase()),
+            modified_at: Some(modified_at),
+            ..Default::default()
+        }
+    }
+
+    fn dir(name: &str) -> IndexNode {
+        IndexNode {
+            name: name.to_string(),
+            is_dir: true,
+            ..Default::default()
+        }
+    }
+
+    /// /r/{a/{Big.ISO, notes.txt}, c/main.rs, readme.md}
+    fn fixture() -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 8);
+        let root = index.insert(None, dir("r"));
+        let a = index.insert(Some(root), dir("a"));
+        index.insert(Some(a), file("Big.ISO", 5000, 100));
+        index.insert(Some(a), file("notes.txt", 100, 200));
+        let c = index.insert(Some(root), dir("c"));
+        index.insert(Some(c), file("main.rs", 300, 300));
+        index.insert(Some(root), file("readme.md", 50, 400));
+        index.recompute_totals();
+        index
+    }
+
+    fn names(query: SearchQuery) -> Vec<String> {
+        search_index(&fixture(), &query, &CategoryTable::default())
+            .unwrap()
+            .hits
+            .into_iter()
+            .map(|hit| hit.name)
+            .collect()
+    }
+
+    #[test]
+    fn substring_respects_case_sensitivity() {
+        let query = SearchQuery {
+            pattern: Some("big".into()),
+            ..Default::default()
+        };
+        assert_eq!(names(query.clone()), vec!["Big.ISO"]);
+        assert!(names(SearchQuery {
+            case_sensitive: true,
+            ..query
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn glob_matches_names_or_full_paths() {
+        assert_eq!(
+            names(SearchQuery {
+                pattern: Some("*.iso".into()),
+                mode: PatternMode::Glob,
+                ..Default::default()
+            }),
+            vec!["Big.ISO"]
+        );
+        assert_eq!(
+            names(SearchQuery {
+                pattern: Some("/r/c/*".into()),
+                mode: PatternMode::Glob,
+                ..Default::default()
+            }),
+            vec!["main.rs"]
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let query = SearchQuery {
+            pattern: Some("(".into()),
+            mode: PatternMode::Regex,
+            ..Default::default()
+        };
+        assert!(search_index(&fixture(), &query, &CategoryTable::default()).is_err());
+    }
+
+    #[test]
+    fn filters_combine() {
+        assert_eq!(
+            names(SearchQuery {
+                pattern: Some(r"\.(rs|md)$".into()),
+                mode: PatternMode::Regex,
+                min_size: Some(60),
+                ..Default::default()
+            }),
+            vec!["main.rs"]
+        );
+        assert_eq!(
+            names(SearchQuery {
+                modified_after: Some(100),
+                modified_before: Some(400),
+                ..Default::default()
+            }),
+            vec!["main.rs", "notes.txt"]
+        );
+        assert_eq!(
+            names(SearchQuery {
+                extensions: vec![".TXT".into(), "md".into()],
+                ..Default::default()
+            }),
+            vec!["notes.txt", "readme.md"]
+        );
+        assert_eq!(
+            names(SearchQuery {
+                categories: vec![FileCategory::Archive],
+                include_dirs: true,
+                ..Default::default()
+            }),
+            vec!["Big.ISO"]
+        );
+    }
+
+    #[test]
+    fn directories_only_when_asked_and_scoped_to_path() {
+        assert_eq!(
+            names(SearchQuery {
+                include_dirs: true,
+                max_size: Some(400),
+                ..Default::default()
+            }),
+            vec!["c", "main.rs", "notes.txt", "readme.md"]
+        );
+        assert_eq!(
+            names(SearchQuery {
+                path: Some("/r/a".into()),
+                ..Default::default()
+            }),
+            vec!["Big.ISO", "notes.txt"]
+        );
+        let missing = SearchQuery {
+            path: Some("/r/missing".into()),
+            ..Default::default()
+        };
+        assert!(search_index(&fixture(), &missing, &CategoryTable::default()).is_err());
+    }
+
+    #[test]
+    fn pages_are_ordered_by_size() {
+        let index = fixture();
+        let page = |offset, limit| {
+            let query = SearchQuery {
+                offset: Some(offset),
+                limit: Some(limit),
+                ..Default::default()
+            };
+            search_index(&index, &query, &CategoryTable::default()).unwrap()
+        };
+
+        let first = page(0, 2);
+        assert_eq!(first.total_matches, 4);
+        assert_eq!(first.offset, 0);
+        let sizes: Vec<u64> = first.hits.iter().map(|hit| hit.size).collect();
+        assert_eq!(sizes, vec![5000, 300]);
+        assert_eq!(first.hits[0].path, "/r/a/Big.ISO");
+
+        let second = page(2, 2);
+        let sizes: Vec<u64> = second.hits.iter().map(|hit| hit.size).collect();
+        assert_eq!(sizes, vec![100, 50]);
+        assert!(page(4, 2).hits.is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+//! User preferences, persisted in the `settings` table.
+//!
+//! Each field is its own row, keyed by the field name with a JSON value, so
+//! a value that no longer loads falls back to its default without taking
+//! the other fields with it, and new fields need no migration.
+
+use crate::cache;
+use crate::error::{AppError, AppResult};
+use crate::logging;
+use crate::refresh::{ActiveRefreshPolicy, PathPatterns, RefreshPolicy};
+use crate::scanner::ScanOptions;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
+
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Options for scans started without their own
+    pub scan_options: ScanOptions,
+    /// Paths left out of every scan and ignored by the watcher, in the same
+    /// syntax as the refresh ignore patterns
+    pub exclude_patterns: Vec<String>,
+    /// How watcher events turn into refreshes
+    pub refresh_policy: RefreshPolicy,
+    /// Polling interval when native watching isn't available
+    pub watch_poll_interval_secs: u64,
+    /// Absolute paths that must never be deleted, along with everything
+    /// inside them
+    pub protected_paths: Vec<String>,
+    /// Whether `delete_permanently` may bypass the trash
+    pub allow_permanent_delete: bool,
+    /// "error", "warn", "info", "debug" or "trace"
+    pub log_level: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            scan_options: ScanOptions::default(),
+            exclude_patterns: Vec::new(),
+            refresh_policy: RefreshPolicy::default(),
+            watch_poll_interval_secs: DEFAULT_POLL_INTERVAL_SECS,
+            protected_paths: Vec::new(),
+            allow_permanent_delete: false,
+            log_level: "info".to_string(),
+        }
+    }
+}
+
+/// A partial update; fields left out keep their current value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SettingsUpdate {
+    pub scan_options: Option<ScanOptions>,
+    pub exclude_patterns: Option<Vec<String>>,
+    pub refresh_policy: Option<RefreshPolicy>,
+    pub watch_poll_interval_secs: Option<u64>,
+    pub protected_paths: Option<Vec<String>>,
+    pub allow_permanent_delete: Option<bool>,
+    pub log_level: Option<String>,
+}
+
+/// Settings with the parts used on hot paths compiled
+#[derive(Clone, Default)]
+pub struct ActiveSettings {
+    pub settings: Settings,
+    pub refresh: ActiveRefreshPolicy,
+    pub excludes: Arc<PathPatterns>,
+}
+
+impl Settings {
+    /// Load the stored settings; missing or invalid fields get their default
+    pub fn load() -> Self {
+        let defaults = Settings::default();
+        Settings {
+            scan_options: load_field("scan_options", defaults.scan_options, ScanOptions::validate),
+            exclude_patterns: load_field(
+                "exclude_patterns",
+                defaults.exclude_patterns,
+                |patterns| check_excludes(patterns),
+            ),
+            refresh_policy: load_field(
+                "refresh_policy",
+                defaults.refresh_policy,
+                |policy| policy.clone().compile().map(|_| ()),
+            ),
+            watch_poll_interval_secs: load_field(
+                "watch_poll_interval_secs",
+                defaults.watch_poll_interval_secs,
+                |seconds| check_poll_interval(*seconds),
+            ),
+            protected_paths: load_field(
+                "protected_paths",
+                defaults.protected_paths,
+                |paths| check_protected_paths(paths),
+            ),
+            allow_permanent_delete: load_field(
+                "allow_permanent_delete",
+                defaults.allow_permanent_delete,
+                |_| Ok(()),
+            ),
+            log_level: load_field("log_level", defaults.log_level, |level| {
+                logging::check_level(level)
+            }),
+        }
+    }
+
+    /// These settings with the fields set in `update` replaced
+    pub fn merge(self, update: SettingsUpdate) -> Settings {
+        Settings {
+            scan_options: update.scan_options.unwrap_or(self.scan_options),
+            exclude_patterns: update.exclude_patterns.unwrap_or(self.exclude_patterns),
+            refresh_policy: update.refresh_policy.unwrap_or(self.refresh_policy),
+            watch_poll_interval_secs: update
+                .watch_poll_interval_secs
+                .unwrap_or(self.watch_poll_interval_secs),
+            protected_paths: update.protected_paths.unwrap_or(self.protected_paths),
+            allow_permanent_delete: update
+                .allow_permanent_delete
+                .unwrap_or(self.allow_permanent_delete),
+            log_level: update.log_level.unwrap_or(self.log_level),
+        }
+    }
+
+    /// Validate every field and compile the parts used on hot paths
+    pub fn compile(self) -> AppResult<ActiveSettings> {
+        self.scan_options.validate()?;
+        check_poll_interval(self.watch_poll_interval_secs)?;
+        check_protected_paths(&self.protected_paths)?;
+        logging::check_level(&self.log_level)?;
+        let excludes = PathPatterns::compile(&self.exclude_patterns, "exclude")?;
+        let refresh = self.refresh_policy.clone().compile()?;
+        Ok(ActiveSettings {
+            settings: self,
+            refresh,
+            excludes: Arc::new(excludes),
+        })
+    }
+
+    /// Store the fields that differ from `previous`
+    pub fn save(&self, previous: &Settings) -> AppResult<()> {
+        if self.scan_options != previous.scan_options {
+            save_field("scan_options", &self.scan_options)?;
+        }
+        if self.exclude_patterns != previous.exclude_patterns {
+            save_field("exclude_patterns", &self.exclude_patterns)?;
+        }
+        if self.refresh_policy != previous.refresh_policy {
+            save_field("refresh_policy", &self.refresh_policy)?;
+        }
+        if self.watch_poll_interval_secs != previous.watch_poll_interval_secs {
+            save_field("watch_poll_interval_secs", &self.watch_poll_interval_secs)?;
+        }
+        if self.protected_paths != previous.protected_paths {
+            save_field("protected_paths", &self.protected_paths)?;
+        }
+        if self.allow_permanent_delete != previous.allow_permanent_delete {
+            save_field("allow_permanent_delete", &self.allow_permanent_delete)?;
+        }
+        if self.log_level != previous.log_level {
+            save_field("log_level", &self.log_level)?;
+        }
+        Ok(())
+    }
+}
+
+fn load_field<T: DeserializeOwned>(
+    key: &str,
+    default: T,
+    check: impl Fn(&T) -> AppResult<()>,
+) -> T {
+    let Some(json) = cache::load_setting(key) else {
+        return default;
+    };
+    let loaded = serde_json::from_str::<T>(&json)
+        .map_err(|e| AppError::invalid(e.to_string()))
+        .and_then(|value| check(&value).map(|_| value));
+    match loaded {
+        Ok(value) => value,
+        Err(e) => {
+            warn!(key, error = %e, "ignoring stored setting");
+            default
+        }
+    }
+}
+
+fn save_field<T: Serialize>(key: &str, value: &T) -> AppResult<()> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| AppError::internal(format!("Failed to encode setting {}: {}", key, e)))?;
+    cache::save_setting(key, &json)
+}
+
+fn check_excludes(patterns: &[String]) -> AppResult<()> {
+    PathPatterns::compile(patterns, "exclude").map(|_| ())
+}
+
+fn check_poll_interval(seconds: u64) -> AppResult<()> {
+    if !(1..=3600).contains(&seconds) {
+        return Err(AppError::invalid("Poll interval must be between 1 and 3600 seconds"));
+    }
+    Ok(())
+}
+
+fn check_protected_paths(paths: &[String]) -> AppResult<()> {
+    match paths.iter().find(|p| !Path::new(p.as_str()).is_absolute()) {
+        Some(path) => Err(AppError::invalid(format!(
+            "Protected paths must be absolute: {}",
+            path
+        ))),
+        None => Ok(()),
+    }
+}
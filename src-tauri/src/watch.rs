@@ -0,0 +1,222 @@
+//! Watch coverage planning for trees larger than the inotify budget.
+//!
+//! inotify needs one watch per directory, and `fs.inotify.max_user_watches`
+//! is shared by every process of the user. When a scan has more directories
+//! than the budget allows, the largest subtrees that fit are watched
+//! natively and the rest of the tree is polled instead.
+
+use crate::index::{NodeId, ScanIndex, ROOT_ID};
+use serde::Serialize;
+use std::collections::BinaryHeap;
+use std::path::PathBuf;
+
+/// Share of the user-wide watch limit left for other processes
+const RESERVED_WATCH_FRACTION: u64 = 4;
+
+/// Upper bound on directories split into shallow polls, so a deep tree with
+/// a tiny remaining budget doesn't get walked node by node
+const MAX_SPLITS: usize = 1024;
+
+/// How a tree too large for the watch budget is divided between native
+/// watches and polling
+#[derive(Debug, Clone, Default)]
+pub struct WatchPlan {
+    /// Subtrees watched natively (recursive)
+    pub native: Vec<PathBuf>,
+    /// Subtrees polled recursively
+    pub poll_recursive: Vec<PathBuf>,
+    /// Directories on the way to native subtrees; only their direct entries
+    /// are polled
+    pub poll_shallow: Vec<PathBuf>,
+    pub budget: u64,
+    pub total_dirs: u64,
+    pub native_dirs: u64,
+}
+
+/// Which parts of the tree get live events, reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchCoverage {
+    pub watch_budget: u64,
+    pub total_dirs: u64,
+    pub native_dirs: u64,
+    /// Subtrees with live (native) events
+    pub live: Vec<String>,
+    /// Subtrees only refreshed on the poll interval
+    pub polled: Vec<String>,
+    /// Directories whose direct entries are polled
+    pub polled_shallow: Vec<String>,
+}
+
+impl WatchPlan {
+    pub fn coverage(&self) -> WatchCoverage {
+        let strings = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect()
+        };
+        WatchCoverage {
+            watch_budget: self.budget,
+            total_dirs: self.total_dirs,
+            native_dirs: self.native_dirs,
+            live: strings(&self.native),
+            polled: strings(&self.poll_recursive),
+            polled_shallow: strings(&self.poll_shallow),
+        }
+    }
+}
+
+/// Usable share of a user-wide watch limit
+pub fn usable_budget(max_user_watches: u64) -> u64 {
+    max_user_watches - max_user_watches / RESERVED_WATCH_FRACTION
+}
+
+/// Split the tree between native watches and polling, or None when every
+/// directory fits in `budget`.
+///
+/// Subtrees are taken largest first (by bytes); one that doesn't fit is
+/// split into its child directories, and whatever is left over once the
+/// budget runs out is polled.
+pub fn plan_watches(index: &ScanIndex, budget: u64) -> Option<WatchPlan> {
+    let root = index.get(ROOT_ID)?;
+    let total_dirs = root.dir_count + 1;
+    if total_dirs <= budget {
+        return None;
+    }
+
+    let mut plan = WatchPlan {
+        budget,
+        total_dirs,
+        ..Default::default()
+    };
+    let mut remaining = budget;
+    let mut heap: BinaryHeap<(u64, NodeId)> = BinaryHeap::new();
+    let push_children = |heap: &mut BinaryHeap<(u64, NodeId)>, id: NodeId| {
+        let Some(node) = index.get(id) else { return };
+        for &child in &node.children {
+            if let Some(child_node) = index.get(child).filter(|c| c.is_dir) {
+                heap.push((child_node.size, child));
+            }
+        }
+    };
+
+    plan.poll_shallow.push(index.path_of(ROOT_ID));
+    push_children(&mut heap, ROOT_ID);
+
+    while remaining > 0 && plan.poll_shallow.len() < MAX_SPLITS {
+        let Some((_, id)) = heap.pop() else { break };
+        let Some(node) = index.get(id) else { continue };
+        let cost = node.dir_count + 1;
+
+        if cost <= remaining {
+            plan.native.push(index.path_of(id));
+            plan.native_dirs += cost;
+            remaining -= cost;
+        } else {
+            // cost > remaining >= 1, so there are child directories to split into
+            plan.poll_shallow.push(index.path_of(id));
+            push_children(&mut heap, id);
+        }
+    }
+
+    plan.poll_recursive
+        .extend(heap.into_iter().map(|(_, id)| index.path_of(id)));
+    Some(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::IndexNode;
+    use std::path::Path;
+
+    /// /r/{big/{s1, s2}, mid/m1, small}, each leaf directory holding one
+    /// file: 7 directories, largest first by bytes
+    fn fixture() -> ScanIndex {
+        let mut index = ScanIndex::with_capacity(Path::new("/r"), 16);
+        let dir = |index: &mut ScanIndex, parent, name: &str| {
+            index.insert(
+                parent,
+                IndexNode {
+                    name: name.to_string(),
+                    is_dir: true,
+                    ..Default::default()
+                },
+            )
+        };
+        let file = |index: &mut ScanIndex, parent, size| {
+            index.insert(
+                Some(parent),
+                IndexNode {
+                    name: "f".to_string(),
+                    size,
+                    ..Default::default()
+                },
+            );
+        };
+
+        let root = dir(&mut index, None, "r");
+        let big = dir(&mut index, Some(root), "big");
+        let s1 = dir(&mut index, Some(big), "s1");
+        file(&mut index, s1, 600);
+        let s2 = dir(&mut index, Some(big), "s2");
+        file(&mut index, s2, 400);
+        let mid = dir(&mut index, Some(root), "mid");
+        let m1 = dir(&mut index, Some(mid), "m1");
+        file(&mut index, m1, 100);
+        let small = dir(&mut index, Some(root), "small");
+        file(&mut index, small, 10);
+        index.recompute_totals();
+        index
+    }
+
+    fn paths(paths: &[PathBuf]) -> Vec<&str> {
+        let mut paths: Vec<&str> = paths.iter().map(|p| p.to_str().unwrap()).collect();
+        paths.sort_unstable();
+        paths
+    }
+
+    #[test]
+    fn no_plan_when_everything_fits() {
+        let index = fixture();
+        assert!(plan_watches(&index, 7).is_none());
+        assert!(plan_watches(&index, 100).is_none());
+    }
+
+    #[test]
+    fn largest_fitting_subtree_is_watched() {
+        let plan = plan_watches(&fixture(), 3).unwrap();
+        assert_eq!(paths(&plan.native), vec!["/r/big"]);
+        assert_eq!(paths(&plan.poll_recursive), vec!["/r/mid", "/r/small"]);
+        assert_eq!(paths(&plan.poll_shallow), vec!["/r"]);
+        assert_eq!((plan.budget, plan.total_dirs, plan.native_dirs), (3, 7, 3));
+    }
+
+    #[test]
+    fn oversized_subtree_is_split_into_children() {
+        let plan = plan_watches(&fixture(), 2).unwrap();
+        assert_eq!(paths(&plan.native), vec!["/r/big/s1", "/r/big/s2"]);
+        assert_eq!(paths(&plan.poll_shallow), vec!["/r", "/r/big"]);
+        assert_eq!(paths(&plan.poll_recursive), vec!["/r/mid", "/r/small"]);
+        assert_eq!(plan.native_dirs, 2);
+
+        let coverage = plan.coverage();
+        assert_eq!(coverage.watch_budget, 2);
+        assert_eq!(coverage.live.len(), 2);
+        assert_eq!(coverage.polled_shallow.len(), 2);
+    }
+
+    #[test]
+    fn zero_budget_polls_everything() {
+        let plan = plan_watches(&fixture(), 0).unwrap();
+        assert!(plan.native.is_empty());
+        assert_eq!(paths(&plan.poll_recursive), vec!["/r/big", "/r/mid", "/r/small"]);
+        assert_eq!(plan.native_dirs, 0);
+    }
+
+    #[test]
+    fn usable_budget_keeps_a_reserve() {
+        assert_eq!(usable_budget(8192), 6144);
+        assert_eq!(usable_budget(0), 0);
+    }
+}
@@ -0,0 +1,79 @@
+//! Workspaces: named sets of scan roots shown as one combined tree.
+//!
+//! All roots of a workspace are walked together, so hard links shared
+//! between them are counted once, and hung under a synthetic root node. The
+//! combined scan is cached under the key `workspace:<name>`, which also
+//! serves as the scan path of the tree (the index resolves real paths
+//! through its top-level roots).
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const WORKSPACE_PREFIX: &str = "workspace:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    pub roots: Vec<String>,
+}
+
+impl Workspace {
+    /// Cache key and scan path of the combined tree
+    pub fn key(&self) -> String {
+        workspace_key(&self.name)
+    }
+
+    /// Check the name and roots, returning the roots as normalized paths.
+    ///
+    /// Roots must be existing directories, and none may contain another
+    /// (the nested one would be walked twice).
+    pub fn validate(&self) -> AppResult<Vec<PathBuf>> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::invalid("Workspace name must not be empty"));
+        }
+        if self.roots.is_empty() {
+            return Err(AppError::invalid("Workspace needs at least one folder"));
+        }
+
+        let mut roots: Vec<PathBuf> = Vec::with_capacity(self.roots.len());
+        for root in &self.roots {
+            // Collecting the components drops trailing slashes and `.` segments
+            let path: PathBuf = Path::new(root).components().collect();
+            if !path.is_absolute() {
+                return Err(AppError::invalid(format!(
+                    "Workspace folders must be absolute paths: {}",
+                    root
+                )));
+            }
+            if !path.exists() {
+                return Err(AppError::not_found(root));
+            }
+            if !path.is_dir() {
+                return Err(AppError::new(
+                    ErrorCode::NotADirectory,
+                    format!("Path is not a directory: {}", root),
+                )
+                .with_path(root));
+            }
+            if let Some(other) = roots.iter().find(|r| r.starts_with(&path) || path.starts_with(r)) {
+                return Err(AppError::invalid(format!(
+                    "Workspace folders overlap: {} and {}",
+                    other.display(),
+                    path.display()
+                )));
+            }
+            roots.push(path);
+        }
+        Ok(roots)
+    }
+}
+
+pub fn workspace_key(name: &str) -> String {
+    format!("{}{}", WORKSPACE_PREFIX, name)
+}
+
+/// The workspace name when `scan_path` is a workspace key
+pub fn workspace_name(scan_path: &str) -> Option<&str> {
+    scan_path.strip_prefix(WORKSPACE_PREFIX)
+}
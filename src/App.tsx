@@ -5,14 +5,23 @@ import { open } from "@tauri-apps/plugin-dialog";
 import {
   FileNode,
   ScanProgress,
+  ScanJobInfo,
+  PartialScan,
   DiskSpaceInfo,
   TreemapRect,
   FileType,
   CachedScan,
   ScanHistoryEntry,
   DeleteLogEntry,
+  LogEntry,
   WatcherStatus,
+  RefreshPolicy,
+  Settings,
   IncrementalStatus,
+  IncrementalPatch,
+  AncestorUpdate,
+  TreeSnapshot,
+  OwnerUsage,
   FILE_TYPE_COLORS,
   FILE_TYPE_NAMES,
   getFileGradient,
@@ -20,6 +29,12 @@ import {
   getFileType,
   formatSize,
   formatDate,
+  formatDuration,
+  WORKSPACE_PREFIX,
+  errorMessage,
+  isAppError,
+  isStaleCacheError,
+  formatLogEntry,
 } from "./types";
 import { layoutTreemap } from "./treemap";
 import { ThemeSwitcher } from "./ThemeSwitcher";
@@ -156,6 +171,12 @@ function App() {
   const [navigationPath, setNavigationPath] = useState<FileNode[]>([]);
   const [isScanning, setIsScanning] = useState(false);
   const [progress, setProgress] = useState<ScanProgress | null>(null);
+  const [scanPaused, setScanPaused] = useState(false);
+  const [partialScan, setPartialScan] = useState<PartialScan | null>(null);
+  // Job id of the scan the UI is showing; progress from other jobs is ignored
+  const activeJobIdRef = useRef<number | null>(null);
+  // Bumped per scan request so a superseded scan's result is dropped
+  const scanRequestRef = useRef(0);
   const [hoveredNode, setHoveredNode] = useState<FileNode | null>(null);
   const [tooltipPos, setTooltipPos] = useState<{ x: number; y: number }>({ x: 0, y: 0 });
   const [filterType, setFilterType] = useState<FileType | null>(null);
@@ -186,8 +207,12 @@ function App() {
   const [deleteLog, setDeleteLog] = useState<DeleteLogEntry[]>([]);
   const [watcherActive, setWatcherActive] = useState(false);
   const [watcherError, setWatcherError] = useState<string | null>(null);
+  const [watcherStatus, setWatcherStatus] = useState<WatcherStatus | null>(null);
   const [isSyncing, setIsSyncing] = useState(false);
   const [syncIsFullRescan, setSyncIsFullRescan] = useState(false);
+  const [liveUpdatesPaused, setLiveUpdatesPaused] = useState(false);
+  const [allowPermanentDelete, setAllowPermanentDelete] = useState(false);
+  const [logsCopied, setLogsCopied] = useState(false);
 
   // Local, CSP-safe background gradients
   const backgrounds = [
@@ -198,12 +223,43 @@ function App() {
   ];
   const errorIdRef = useRef(0);
   const containerRef = useRef<HTMLDivElement>(null);
+  // Sequence number of the last applied "scan-incremental" patch
+  const patchSeqRef = useRef(0);
+  // Latest tree and current node for the incremental listener, which
+  // subscribes once; patches arriving back to back must chain on each other
+  const rootNodeRef = useRef<FileNode | null>(null);
+  const currentNodeRef = useRef<FileNode | null>(null);
+
+  useEffect(() => {
+    rootNodeRef.current = rootNode;
+  }, [rootNode]);
+
+  useEffect(() => {
+    currentNodeRef.current = currentNode;
+  }, [currentNode]);
 
   // Load scan history on mount
   useEffect(() => {
     invoke<ScanHistoryEntry[]>("get_scan_history").then(setScanHistory).catch(console.error);
   }, []);
 
+  // Load settings on mount and follow changes made from any window
+  useEffect(() => {
+    invoke<Settings>("get_settings")
+      .then((settings) => {
+        setLiveUpdatesPaused(settings.refresh_policy.paused);
+        setAllowPermanentDelete(settings.allow_permanent_delete);
+      })
+      .catch(console.error);
+    const unlisten = listen<Settings>("settings-changed", (event) => {
+      setLiveUpdatesPaused(event.payload.refresh_policy.paused);
+      setAllowPermanentDelete(event.payload.allow_permanent_delete);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Load delete log for the current scan path
   useEffect(() => {
     if (!currentScanPath) {
@@ -230,7 +286,17 @@ function App() {
 
   // Listen for scan progress events
   useEffect(() => {
+    const unlistenStarted = listen<ScanJobInfo>("scan-started", (event) => {
+      activeJobIdRef.current = event.payload.job_id;
+    });
+
+    const unlistenPartial = listen<PartialScan>("scan-partial", (event) => {
+      if (event.payload.job_id !== activeJobIdRef.current) return;
+      setPartialScan(event.payload);
+    });
+
     const unlistenProgress = listen<ScanProgress>("scan-progress", (event) => {
+      if (event.payload.job_id !== activeJobIdRef.current) return;
       setProgress(event.payload);
       if (event.payload.is_complete) {
         setIsScanning(false);
@@ -254,6 +320,7 @@ function App() {
     const unlistenWatcher = listen<WatcherStatus>("watcher-status", (event) => {
       setWatcherActive(event.payload.active);
       setWatcherError(event.payload.error ?? null);
+      setWatcherStatus(event.payload);
     });
 
     const unlistenIncrementalStatus = listen<IncrementalStatus>("incremental-status", (event) => {
@@ -270,6 +337,8 @@ function App() {
     });
 
     return () => {
+      unlistenStarted.then((fn) => fn());
+      unlistenPartial.then((fn) => fn());
       unlistenProgress.then((fn) => fn());
       unlistenCache.then((fn) => fn());
       unlistenWatcher.then((fn) => fn());
@@ -327,7 +396,29 @@ function App() {
 
       await scanPath(selectedPath, false);
     } catch (error) {
-      showError(`Scan failed: ${error}`);
+      showError(`Scan failed: ${errorMessage(error)}`);
+      setIsScanning(false);
+    }
+  };
+
+  // Pick several folders and scan them as one combined workspace
+  const handleOpenWorkspace = async () => {
+    try {
+      const selected = await open({
+        directory: true,
+        multiple: true,
+        title: "Select folders to combine",
+      });
+      if (!selected || selected.length === 0) return;
+
+      const defaultName = selected.map((p) => p.split("/").filter(Boolean).pop() ?? p).join(" + ");
+      const name = window.prompt("Workspace name", defaultName);
+      if (!name) return;
+
+      const key = await invoke<string>("save_workspace", { name, roots: selected });
+      await scanPath(key, true);
+    } catch (error) {
+      showError(`Workspace scan failed: ${errorMessage(error)}`);
       setIsScanning(false);
     }
   };
@@ -336,30 +427,46 @@ function App() {
     try {
       setCurrentScanPath(path);
 
-      // Fetch disk info for the selected path
-      try {
-        const info = await invoke<DiskSpaceInfo>("get_disk_info", { path });
-        setDiskInfo(info);
-      } catch (e) {
-        showError(`Failed to get disk info: ${e}`, 'warning');
+      // Fetch disk info for the selected path (a workspace spans several disks)
+      if (path.startsWith(WORKSPACE_PREFIX)) {
+        setDiskInfo(null);
+      } else {
+        try {
+          const info = await invoke<DiskSpaceInfo>("get_disk_info", { path });
+          setDiskInfo(info);
+        } catch (e) {
+          showError(`Failed to get disk info: ${errorMessage(e)}`, 'warning');
+        }
       }
 
       // Start scanning (use_cache: false to force rescan)
       setIsScanning(true);
       setProgress(null);
+      setScanPaused(false);
+      setPartialScan(null);
+      const request = ++scanRequestRef.current;
+      activeJobIdRef.current = null;
       const result = await invoke<FileNode | null>("scan_directory", {
         path,
         use_cache: !forceRescan,
       });
+      if (request !== scanRequestRef.current) return;
       if (result) {
+        patchSeqRef.current = 0;
         setRootNode(result);
         setCurrentNode(result);
         setNavigationPath([]);
       }
       setIsScanning(false);
     } catch (error) {
-      showError(`Scan failed: ${error}`);
       setIsScanning(false);
+      // A stale or damaged cache can't be loaded; offer a fresh scan
+      if (!forceRescan && isStaleCacheError(error) &&
+          window.confirm(`${errorMessage(error)}\n\nRescan ${path}?`)) {
+        await scanPath(path, true);
+        return;
+      }
+      showError(`Scan failed: ${errorMessage(error)}`);
     }
   };
 
@@ -372,20 +479,50 @@ function App() {
   };
 
   const handleCancelScan = async () => {
-    await invoke("cancel_scan");
+    await invoke("cancel_scan", { job_id: activeJobIdRef.current });
     setIsScanning(false);
+    setScanPaused(false);
   };
 
+  // Abandon the running scan and scan one of its provisional children instead
+  const handleDrillIntoPartial = async (path: string) => {
+    await invoke("cancel_scan", { job_id: activeJobIdRef.current });
+    await scanPath(path);
+  };
+
+  const handleTogglePauseScan = async () => {
+    const command = scanPaused ? "resume_scan" : "pause_scan";
+    try {
+      const changed = await invoke<boolean>(command, { job_id: activeJobIdRef.current });
+      if (changed) {
+        setScanPaused(!scanPaused);
+      }
+    } catch (error) {
+      showError(`Failed to ${scanPaused ? "resume" : "pause"} scan: ${errorMessage(error)}`, 'warning');
+    }
+  };
+
+  // Per-user usage for exports (empty if the backend has no index loaded)
+  const fetchOwnerUsage = useCallback(async (): Promise<OwnerUsage[]> => {
+    try {
+      return await invoke<OwnerUsage[]>("get_owner_usage", { path: null });
+    } catch {
+      return [];
+    }
+  }, []);
+
   // Export scan results
-  const exportAsJSON = useCallback(() => {
+  const exportAsJSON = useCallback(async () => {
     if (!rootNode) return;
 
+    const owners = await fetchOwnerUsage();
     const data = {
       scanPath: rootNode.path,
       exportedAt: new Date().toISOString(),
       totalSize: rootNode.size,
       totalFiles: rootNode.file_count,
       totalDirs: rootNode.dir_count,
+      owners,
       tree: rootNode,
     };
 
@@ -396,12 +533,21 @@ function App() {
     a.download = `spaceview-export-${Date.now()}.json`;
     a.click();
     URL.revokeObjectURL(url);
-  }, [rootNode]);
+  }, [rootNode, fetchOwnerUsage]);
 
-  const exportAsCSV = useCallback(() => {
+  const exportAsCSV = useCallback(async () => {
     if (!rootNode) return;
 
-    const rows: string[] = ["Path,Name,Type,Size (bytes),Size (human),Is Directory,File Count,Dir Count"];
+    const owners = await fetchOwnerUsage();
+    const usernames = new Map<number, string>();
+    owners.forEach(o => {
+      if (o.uid !== undefined && o.uid !== null && o.username) usernames.set(o.uid, o.username);
+    });
+    const ownerName = (uid?: number) =>
+      uid === undefined || uid === null ? "" : usernames.get(uid) ?? uid.toString();
+    const isoDate = (ts?: number) => (ts ? new Date(ts * 1000).toISOString() : "");
+
+    const rows: string[] = ["Path,Name,Type,Size (bytes),Size (human),Is Directory,File Count,Dir Count,Owner,UID,GID,Modified,Accessed"];
 
     const collectRows = (node: FileNode, depth = 0) => {
       const type = getFileType(node);
@@ -414,6 +560,11 @@ function App() {
         node.is_dir ? "Yes" : "No",
         node.file_count.toString(),
         node.dir_count.toString(),
+        `"${ownerName(node.uid).replace(/"/g, '""')}"`,
+        node.uid?.toString() ?? "",
+        node.gid?.toString() ?? "",
+        isoDate(node.modified_at),
+        isoDate(node.accessed_at),
       ].join(","));
 
       if (depth < 5) { // Limit depth for CSV export
@@ -430,7 +581,7 @@ function App() {
     a.download = `spaceview-export-${Date.now()}.csv`;
     a.click();
     URL.revokeObjectURL(url);
-  }, [rootNode]);
+  }, [rootNode, fetchOwnerUsage]);
 
   // Zoom with mouse wheel
   const handleWheel = useCallback((e: React.WheelEvent) => {
@@ -567,26 +718,96 @@ function App() {
 
   const handleIncrementalUpdate = useCallback(
     (nextRoot: FileNode) => {
+      rootNodeRef.current = nextRoot;
       setRootNode(nextRoot);
-      const desiredPath = currentNode?.path || nextRoot.path;
+      const desiredPath = currentNodeRef.current?.path || nextRoot.path;
       const nextCurrent = findNodeByPath(nextRoot, desiredPath) || nextRoot;
+      currentNodeRef.current = nextCurrent;
       setCurrentNode(nextCurrent);
       setNavigationPath(buildNavigationPath(nextRoot, nextCurrent.path));
       setIsFromCache(false);
       setIsScanning(false);
     },
-    [currentNode, findNodeByPath, buildNavigationPath]
+    [findNodeByPath, buildNavigationPath]
   );
 
+  // Apply a patch: drop removed paths, swap in replaced subtrees, and update
+  // totals along the ancestor chain. Subtrees folded into "more items" nodes are skipped.
+  const applyIncrementalPatch = useCallback(
+    (root: FileNode, patch: IncrementalPatch): FileNode => {
+      const replaced = new Map<string, FileNode>(patch.subtrees.map((s) => [s.path, s.node]));
+      const ancestors = new Map<string, AncestorUpdate>(patch.ancestors.map((a) => [a.path, a]));
+      const removed = new Set(patch.removed);
+
+      const patchNode = (node: FileNode): FileNode => {
+        const replacement = replaced.get(node.path);
+        if (replacement && node.id === node.path) return replacement;
+        const totals = ancestors.get(node.path);
+        if (!totals || !node.is_dir) return node;
+
+        const children = node.children.filter((c) => !removed.has(c.id)).map(patchNode);
+        // Subtrees that are new under this directory
+        for (const [path, subtree] of replaced) {
+          const parent = path.slice(0, path.lastIndexOf("/")) || "/";
+          if (parent === node.path && !children.some((c) => c.id === path)) {
+            children.push(subtree);
+          }
+        }
+        children.sort((a, b) => {
+          const aOther = a.id.endsWith("/__other__");
+          const bOther = b.id.endsWith("/__other__");
+          if (aOther !== bOther) return aOther ? 1 : -1;
+          return b.size - a.size;
+        });
+
+        return {
+          ...node,
+          children,
+          size: totals.size,
+          file_count: totals.file_count,
+          dir_count: totals.dir_count,
+        };
+      };
+
+      return patchNode(root);
+    },
+    []
+  );
+
+  const resyncTree = useCallback(async () => {
+    try {
+      const snapshot = await invoke<TreeSnapshot>("resync_tree");
+      patchSeqRef.current = snapshot.seq;
+      if (snapshot.root) {
+        handleIncrementalUpdate(snapshot.root);
+      }
+    } catch (error) {
+      showError(`Failed to resync tree: ${errorMessage(error)}`, 'warning');
+    }
+  }, [handleIncrementalUpdate, showError]);
+
   useEffect(() => {
-    const unlistenIncremental = listen<FileNode>("scan-incremental", (event) => {
-      handleIncrementalUpdate(event.payload);
+    const unlistenIncremental = listen<IncrementalPatch>("scan-incremental", (event) => {
+      const patch = event.payload;
+      if (patch.root) {
+        patchSeqRef.current = patch.seq;
+        handleIncrementalUpdate(patch.root);
+        return;
+      }
+      // A gap means a patch was missed; fetch the current tree instead
+      const root = rootNodeRef.current;
+      if (!root || patch.seq !== patchSeqRef.current + 1) {
+        resyncTree();
+        return;
+      }
+      patchSeqRef.current = patch.seq;
+      handleIncrementalUpdate(applyIncrementalPatch(root, patch));
     });
 
     return () => {
       unlistenIncremental.then((fn) => fn());
     };
-  }, [handleIncrementalUpdate]);
+  }, [handleIncrementalUpdate, applyIncrementalPatch, resyncTree]);
 
   const navigateToIndex = useCallback(
     (index: number) => {
@@ -615,7 +836,7 @@ function App() {
     try {
       await invoke("show_in_finder", { path });
     } catch (e) {
-      showError(`Failed to show in Finder: ${e}`);
+      showError(`Failed to show in Finder: ${errorMessage(e)}`);
     }
     setContextMenu(null);
   };
@@ -624,33 +845,65 @@ function App() {
     try {
       await invoke("open_file", { path });
     } catch (e) {
-      showError(`Failed to open file: ${e}`);
+      showError(`Failed to open file: ${errorMessage(e)}`);
     }
     setContextMenu(null);
   };
 
+  // Copy recent backend logs to the clipboard for bug reports
+  const handleCopyLogs = async () => {
+    try {
+      const entries = await invoke<LogEntry[]>("get_recent_logs", { limit: 1000 });
+      await navigator.clipboard.writeText(entries.map(formatLogEntry).join("\n"));
+      setLogsCopied(true);
+      setTimeout(() => setLogsCopied(false), 2000);
+    } catch (e) {
+      showError(`Failed to copy logs: ${errorMessage(e)}`, 'warning');
+    }
+  };
+
   const handleIncrementalRefresh = async () => {
     try {
       setIsSyncing(true);
       await invoke("refresh_incremental");
     } catch (e) {
-      showError(`Incremental refresh failed: ${e}`, 'warning');
+      showError(`Incremental refresh failed: ${errorMessage(e)}`, 'warning');
       setIsSyncing(false);
     }
   };
 
-  const handleMoveToTrash = async (path: string) => {
+  const handleToggleLiveUpdates = async () => {
     try {
-      await invoke("move_to_trash_logged", {
-        path,
-        scan_path: rootNode?.path || undefined,
-        size_bytes: contextMenu?.node.size || undefined,
+      const policy = await invoke<RefreshPolicy>("set_live_updates_paused", {
+        paused: !liveUpdatesPaused,
       });
+      setLiveUpdatesPaused(policy.paused);
+    } catch (e) {
+      showError(`Failed to update live updates: ${errorMessage(e)}`, 'warning');
+    }
+  };
+
+  const handleMoveToTrash = async (path: string, permanent = false) => {
+    if (permanent && !window.confirm(`Delete ${path} permanently? It can't be restored.`)) {
+      return;
+    }
+    try {
+      if (permanent) {
+        await invoke("delete_permanently", {
+          path,
+          scan_path: rootNode?.path || undefined,
+        });
+      } else {
+        await invoke("move_to_trash_logged", {
+          path,
+          scan_path: rootNode?.path || undefined,
+        });
+      }
       setContextMenu(null);
 
       if (rootNode) {
         invoke("refresh_incremental").catch((e) =>
-          showError(`Failed to sync changes: ${e}`, 'warning')
+          showError(`Failed to sync changes: ${errorMessage(e)}`, 'warning')
         );
 
         const { updated, removed } = removeNodeFromTree(rootNode, path);
@@ -673,8 +926,21 @@ function App() {
           .catch(console.error);
       }
     } catch (e) {
-      showError(`Failed to move to trash: ${e}`);
+      showError(`Failed to ${permanent ? "delete" : "move to trash"}: ${errorMessage(e)}`);
       setContextMenu(null);
+      // The tree is out of date; bring it up to date before another try
+      if (isAppError(e) && e.code === "size_changed") {
+        invoke("refresh_incremental").catch(console.error);
+      }
+    }
+  };
+
+  const handleRestoreDeleted = async (entry: DeleteLogEntry) => {
+    try {
+      const restored = await invoke<DeleteLogEntry>("restore_deleted", { log_id: entry.id });
+      setDeleteLog((prev) => prev.map((e) => (e.id === restored.id ? restored : e)));
+    } catch (e) {
+      showError(`Failed to restore: ${errorMessage(e)}`);
     }
   };
 
@@ -841,6 +1107,15 @@ function App() {
           <span aria-hidden="true">&#128193;</span> Open Folder
         </button>
 
+        <button
+          className="toolbar-btn"
+          onClick={handleOpenWorkspace}
+          disabled={isScanning}
+          aria-label="Scan several folders as one workspace"
+        >
+          <span aria-hidden="true">&#128450;</span> Workspace
+        </button>
+
         {isScanning && (
           <button
             className="toolbar-btn"
@@ -1041,6 +1316,15 @@ function App() {
           )}
         </div>
 
+        <button
+          className="toolbar-btn"
+          onClick={handleCopyLogs}
+          aria-label="Copy recent logs for a bug report"
+          title="Copy recent logs for a bug report"
+        >
+          <span aria-hidden="true">&#128203;</span> {logsCopied ? "Copied" : "Logs"}
+        </button>
+
         <ThemeSwitcher />
       </div>
 
@@ -1060,8 +1344,25 @@ function App() {
           </div>
           <div className="trust-item">
             <span className="trust-label">Watcher</span>
-            <span className={`trust-value${watcherActive ? " trust-live" : " trust-off"}`}>
-              {watcherActive ? "Live" : "Off"}
+            <span
+              className={`trust-value${watcherActive ? " trust-live" : " trust-off"}`}
+              title={
+                watcherStatus?.coverage
+                  ? `${watcherStatus.reason}\nLive: ${watcherStatus.coverage.live.join(", ")}\nPolled: ${watcherStatus.coverage.polled.join(", ")}`
+                  : watcherStatus?.reason
+                    ? watcherActive
+                      ? `Polling: ${watcherStatus.reason}`
+                      : watcherStatus.reason
+                    : undefined
+              }
+            >
+              {!watcherActive
+                ? "Off"
+                : watcherStatus?.mode === "poll"
+                  ? `Polling (${watcherStatus.poll_interval_secs}s)`
+                  : watcherStatus?.mode === "hybrid" && watcherStatus.coverage
+                    ? `Partial (${watcherStatus.coverage.native_dirs}/${watcherStatus.coverage.total_dirs} dirs live)`
+                    : "Live"}
             </span>
           </div>
           <button
@@ -1072,6 +1373,13 @@ function App() {
           >
             {isSyncing ? "Syncing..." : "Sync"}
           </button>
+          <button
+            className="trust-refresh-btn"
+            onClick={handleToggleLiveUpdates}
+            title={liveUpdatesPaused ? "Resume live updates" : "Pause live updates"}
+          >
+            {liveUpdatesPaused ? "Resume" : "Pause"}
+          </button>
           {syncIsFullRescan && (
             <div className="trust-item">
               <span className="trust-label">Mode</span>
@@ -1181,6 +1489,18 @@ function App() {
                 </span>
                 <span className="delete-log-size">{formatSize(entry.size_bytes)}</span>
                 <span className="delete-log-time">{formatDate(entry.deleted_at)}</span>
+                {entry.restored_at ? (
+                  <span className="delete-log-status">Restored</span>
+                ) : entry.mode !== "trash" ? (
+                  <span className="delete-log-status">Permanent</span>
+                ) : (
+                  <button
+                    className="delete-log-undo"
+                    onClick={() => handleRestoreDeleted(entry)}
+                  >
+                    Undo
+                  </button>
+                )}
               </div>
             ))}
           </div>
@@ -1232,16 +1552,34 @@ function App() {
             </div>
             <h2>Scanning Directory</h2>
             <p className="scanning-subtitle">
-              {progress?.phase === "walking" && "Scanning files..."}
-              {progress?.phase === "relations" && "Building relationships..."}
-              {progress?.phase === "sizes" && "Calculating sizes..."}
-              {progress?.phase === "tree" && "Building visualization..."}
-              {!progress?.phase && "Analyzing file structure..."}
+              {scanPaused && "Paused"}
+              {!scanPaused && progress?.phase === "walking" && "Scanning files..."}
+              {!scanPaused && progress?.phase === "relations" && "Building relationships..."}
+              {!scanPaused && progress?.phase === "sizes" && "Calculating sizes..."}
+              {!scanPaused && progress?.phase === "tree" && "Building visualization..."}
+              {!scanPaused && !progress?.phase && "Analyzing file structure..."}
             </p>
 
             <div className="scanning-progress">
-              <div className="scanning-progress-bar scanning-progress-indeterminate" />
+              {progress?.percent != null ? (
+                <div
+                  className="scanning-progress-bar"
+                  style={{ width: `${progress.percent}%` }}
+                />
+              ) : (
+                <div
+                  className="scanning-progress-bar scanning-progress-indeterminate"
+                  style={scanPaused ? { animationPlayState: "paused" } : undefined}
+                />
+              )}
             </div>
+            {progress && (
+              <p className="scanning-subtitle">
+                {progress.percent != null && `${Math.floor(progress.percent)}%`}
+                {progress.eta_ms != null && progress.eta_ms > 0 && ` · about ${formatDuration(progress.eta_ms)} left`}
+                {progress.entries_per_sec > 0 && ` · ${progress.entries_per_sec.toLocaleString()} items/s`}
+              </p>
+            )}
 
             <div className="scanning-stats-grid">
               <div className="scanning-stat">
@@ -1264,6 +1602,30 @@ function App() {
               </div>
             </div>
 
+            {partialScan && partialScan.children.length > 0 && (
+              <ul className="scanning-partial" aria-label="Largest items so far">
+                {partialScan.children.slice(0, 8).map((child) => (
+                  <li key={child.path}>
+                    <button
+                      className="scanning-partial-item"
+                      onClick={() => child.is_dir && handleDrillIntoPartial(child.path)}
+                      disabled={!child.is_dir}
+                      title={child.is_dir ? `Scan ${child.path} instead` : child.path}
+                    >
+                      <span className="scanning-partial-name">{child.name}</span>
+                      <span className="scanning-partial-size">{formatSize(child.size)}</span>
+                      <span
+                        className="scanning-partial-bar"
+                        style={{
+                          width: `${partialScan.total_size > 0 ? (child.size / partialScan.total_size) * 100 : 0}%`,
+                        }}
+                      />
+                    </button>
+                  </li>
+                ))}
+              </ul>
+            )}
+
             {progress?.current_path && (
               <div className="scanning-path">
                 <span className="scanning-path-label">Current:</span>
@@ -1271,6 +1633,14 @@ function App() {
               </div>
             )}
 
+            <button
+              className="scanning-cancel-btn"
+              onClick={handleTogglePauseScan}
+              aria-label={scanPaused ? "Resume scanning" : "Pause scanning"}
+            >
+              {scanPaused ? "Resume Scan" : "Pause Scan"}
+            </button>
+
             <button
               className="scanning-cancel-btn"
               onClick={handleCancelScan}
@@ -1378,6 +1748,14 @@ function App() {
           >
             <span>&#128465;</span> Move to Trash
           </div>
+          {allowPermanentDelete && (
+            <div
+              className="context-menu-item danger"
+              onClick={() => handleMoveToTrash(contextMenu.node.path, true)}
+            >
+              <span>&#9888;</span> Delete Permanently
+            </div>
+          )}
         </div>
       )}
 
@@ -1,11 +1,12 @@
-import React, { useMemo } from "react";
-import { FileNode, FileType, FILE_TYPE_COLORS, FILE_TYPE_NAMES, getFileType, formatSize } from "./types";
+import React, { useEffect, useMemo, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { FileNode, FileType, TypeStats, FILE_TYPE_COLORS, FILE_TYPE_NAMES, getFileType, formatSize } from "./types";
 
 interface FileTypeChartProps {
   node: FileNode;
 }
 
-interface TypeStats {
+interface ChartSlice {
   type: FileType;
   size: number;
   count: number;
@@ -13,7 +14,29 @@ interface TypeStats {
 }
 
 export const FileTypeChart: React.FC<FileTypeChartProps> = ({ node }) => {
+  // Whole-scan totals from the backend index; the frontend tree is truncated
+  const [backendStats, setBackendStats] = useState<TypeStats | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<TypeStats>("get_type_stats", { path: node.path })
+      .then((s) => { if (!cancelled) setBackendStats(s); })
+      .catch(() => { if (!cancelled) setBackendStats(null); });
+    return () => { cancelled = true; };
+  }, [node]);
+
   const stats = useMemo(() => {
+    if (backendStats) {
+      return backendStats.by_category
+        .filter(c => c.bytes > 0)
+        .map(c => ({
+          type: c.category as FileType,
+          size: c.bytes,
+          count: c.count,
+          percentage: backendStats.total_bytes > 0 ? (c.bytes / backendStats.total_bytes) * 100 : 0,
+        }));
+    }
+
     const typeMap = new Map<FileType, { size: number; count: number }>();
 
     // Initialize all types
@@ -36,7 +59,7 @@ export const FileTypeChart: React.FC<FileTypeChartProps> = ({ node }) => {
 
     // Convert to array and calculate percentages
     const totalSize = Array.from(typeMap.values()).reduce((sum, v) => sum + v.size, 0);
-    const result: TypeStats[] = allTypes
+    const result: ChartSlice[] = allTypes
       .map(type => ({
         type,
         size: typeMap.get(type)!.size,
@@ -47,7 +70,7 @@ export const FileTypeChart: React.FC<FileTypeChartProps> = ({ node }) => {
       .sort((a, b) => b.size - a.size);
 
     return result;
-  }, [node]);
+  }, [node, backendStats]);
 
   // Generate conic gradient for pie chart
   const gradient = useMemo(() => {
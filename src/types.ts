@@ -9,15 +9,59 @@ export interface FileNode {
   file_count: number;
   dir_count: number;
   modified_at?: number;  // Unix timestamp in seconds
+  accessed_at?: number;  // Unix timestamp in seconds
+  uid?: number;
+  gid?: number;
 }
 
 export interface ScanProgress {
+  job_id: number;
   scanned_files: number;
   scanned_dirs: number;
   current_path: string;
   total_size: number;
   is_complete: boolean;
+  is_paused: boolean;
   phase: string;  // "walking" | "relations" | "sizes" | "tree" | "complete"
+  percent?: number | null;         // 0-100; null while walking without an estimate
+  phase_progress?: number | null;  // 0-1 within the current phase
+  eta_ms?: number | null;
+  entries_per_sec: number;
+  estimate_source?: "previous_scan" | "inodes" | null;
+}
+
+export interface PartialEntry {
+  name: string;
+  path: string;
+  size: number;
+  is_dir: boolean;
+  file_count: number;
+  dir_count: number;
+}
+
+// Provisional sizes of the scan root's children while the walk runs
+export interface PartialScan {
+  job_id: number;
+  root_path: string;
+  total_size: number;
+  children: PartialEntry[];  // largest first
+}
+
+// Scan paths starting with this prefix name a workspace, not a folder
+export const WORKSPACE_PREFIX = "workspace:";
+
+export interface Workspace {
+  name: string;
+  roots: string[];
+}
+
+export interface ScanJobInfo {
+  job_id: number;
+  kind: "full" | "refresh" | "measure";
+  path: string;
+  started_at: number;  // Unix timestamp in seconds
+  cancelled: boolean;
+  paused: boolean;
 }
 
 export interface DiskSpaceInfo {
@@ -42,6 +86,7 @@ export interface CachedScan {
   total_dirs: number;
   total_size: number;
   root: FileNode;
+  extension_stats: ExtensionStat[];
 }
 
 export interface ScanHistoryEntry {
@@ -59,14 +104,130 @@ export interface DeleteLogEntry {
   target_path: string;
   size_bytes: number;
   deleted_at: number;
+  // Set for entries deleted together by execute_delete
+  batch_id: string | null;
+  // Set once restore_deleted put the item back
+  restored_at: number | null;
+  // File type and files removed at deletion time; null for older entries
+  extension: string | null;
+  category: FileCategory | null;
+  file_count: number | null;
+  mode: DeleteMode;
+}
+
+// How a logged item was removed; overwrite zeroed a file before unlinking it
+export type DeleteMode = "trash" | "permanent" | "overwrite";
+
+export type StatsPeriod = "day" | "week" | "month";
+
+export interface DeleteStatsQuery {
+  scan_path?: string;
+  // Unix seconds; since is inclusive, until exclusive
+  since?: number;
+  until?: number;
+  period?: StatsPeriod;
+  extension_limit?: number;
+}
+
+export interface ReclaimedGroup {
+  // Period start ("2024-05-06", "2024-05"), scan path, category or extension
+  key: string | null;
+  bytes: number;
+  deletes: number;
+  files: number;
+}
+
+export interface DeleteStats {
+  total_bytes: number;
+  total_deletes: number;
+  total_files: number;
+  by_period: ReclaimedGroup[];
+  by_scan_path: ReclaimedGroup[];
+  by_category: ReclaimedGroup[];
+  by_extension: ReclaimedGroup[];
+}
+
+export interface PlannedDelete {
+  path: string;
+  size_bytes: number;
+}
+
+export interface CollapsedPath {
+  path: string;
+  covered_by: string;
+}
+
+export interface DeletePlan {
+  // Pass to execute_delete; valid once, for ten minutes
+  token: string;
+  scan_path: string;
+  items: PlannedDelete[];
+  collapsed: CollapsedPath[];
+  violations: AppError[];
+  total_bytes: number;
+}
+
+export interface DeleteItemResult {
+  path: string;
+  deleted: boolean;
+  size_bytes: number;
+  error: AppError | null;
+}
+
+export interface DeleteOutcome {
+  batch_id: string;
+  results: DeleteItemResult[];
+  deleted_count: number;
+  deleted_bytes: number;
+}
+
+export interface WatchCoverage {
+  watch_budget: number;
+  total_dirs: number;
+  native_dirs: number;
+  live: string[];
+  polled: string[];
+  polled_shallow: string[];
 }
 
 export interface WatcherStatus {
   active: boolean;
   path: string;
+  mode: "native" | "poll" | "hybrid";
+  poll_interval_secs?: number;
+  reason?: string;
+  coverage?: WatchCoverage;
   error?: string;
 }
 
+export interface ScanOptions {
+  threads?: number | null;      // null = one per CPU
+  io_priority?: "normal" | "idle";
+  nice?: number | null;         // 0-19
+  max_files_per_sec?: number | null;
+}
+
+export interface RefreshPolicy {
+  debounce_ms: number;
+  max_wait_ms: number;
+  full_rescan_threshold: number;
+  ignore_patterns: string[];
+  paused: boolean;
+  scan_options: ScanOptions;
+}
+
+// Result of get_settings and payload of "settings-changed"; update_settings
+// takes any subset of the fields
+export interface Settings {
+  scan_options: ScanOptions;      // Used when a scan doesn't pass its own
+  exclude_patterns: string[];     // Same syntax as RefreshPolicy.ignore_patterns
+  refresh_policy: RefreshPolicy;
+  watch_poll_interval_secs: number;
+  protected_paths: string[];      // Never deleted, nor anything inside
+  allow_permanent_delete: boolean; // Lets delete_permanently skip the trash
+  log_level: "error" | "warn" | "info" | "debug" | "trace";
+}
+
 export interface IncrementalStatus {
   phase: "start" | "complete";
   updated: boolean;
@@ -75,6 +236,178 @@ export interface IncrementalStatus {
   at: number;
 }
 
+export interface SubtreePatch {
+  path: string;
+  node: FileNode;
+}
+
+export interface AncestorUpdate {
+  path: string;
+  size: number;
+  file_count: number;
+  dir_count: number;
+}
+
+// Payload of "scan-incremental"; `root` replaces the whole tree when set
+export interface IncrementalPatch {
+  seq: number;
+  root: FileNode | null;
+  subtrees: SubtreePatch[];
+  // Moved subtrees appear here under their old path and in `subtrees`
+  removed: string[];
+  ancestors: AncestorUpdate[];
+}
+
+export interface TreeSnapshot {
+  seq: number;
+  root: FileNode | null;
+}
+
+export interface LogEntry {
+  timestamp_ms: number;
+  level: string;
+  target: string;
+  spans: string;  // Enclosing spans, outermost first ("scan:scan_phase")
+  message: string;
+}
+
+export function formatLogEntry(entry: LogEntry): string {
+  const time = new Date(entry.timestamp_ms).toISOString();
+  const scope = entry.spans ? `${entry.target} [${entry.spans}]` : entry.target;
+  return `${time} ${entry.level.padStart(5)} ${scope}: ${entry.message}`;
+}
+
+// Error returned by every command; branch on `code`, not on the message
+export type ErrorCode =
+  | "not_found"
+  | "not_a_directory"
+  | "permission_denied"
+  | "scan_in_progress"
+  | "no_scan"
+  | "not_indexed"
+  | "cache_miss"
+  | "cache_version_mismatch"
+  | "cache_corrupt"
+  | "cache_too_large"
+  | "protected_path"
+  | "outside_scan_root"
+  | "mount_point"
+  | "size_changed"
+  | "permanent_delete_disabled"
+  | "cancelled"
+  | "invalid_input"
+  | "unsupported"
+  | "database"
+  | "io"
+  | "internal";
+
+export interface AppError {
+  code: ErrorCode;
+  message: string;
+  path?: string;
+  context?: string;
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return typeof error === "object" && error !== null && "code" in error && "message" in error;
+}
+
+export function errorMessage(error: unknown): string {
+  return isAppError(error) ? error.message : String(error);
+}
+
+// Cached data that can't be used as is; a fresh scan replaces it
+export function isStaleCacheError(error: unknown): boolean {
+  return isAppError(error) && (error.code === "cache_version_mismatch" || error.code === "cache_corrupt");
+}
+
+export interface SearchQuery {
+  path?: string;
+  pattern?: string;
+  mode?: "substring" | "glob" | "regex";
+  case_sensitive?: boolean;
+  min_size?: number;
+  max_size?: number;
+  modified_before?: number;  // Unix timestamp in seconds
+  modified_after?: number;   // Unix timestamp in seconds
+  extensions?: string[];
+  categories?: FileCategory[];
+  include_dirs?: boolean;
+  offset?: number;
+  limit?: number;
+}
+
+export interface SearchHit {
+  path: string;
+  name: string;
+  size: number;
+  is_dir: boolean;
+  extension?: string;
+  modified_at?: number;
+}
+
+export interface SearchResults {
+  total_matches: number;
+  offset: number;
+  hits: SearchHit[];
+}
+
+export interface TopEntry {
+  path: string;
+  size: number;
+  is_dir: boolean;
+  modified_at?: number;
+}
+
+export interface TopItems {
+  files: TopEntry[];
+  dirs: TopEntry[];
+}
+
+export interface ExtensionStat {
+  extension?: string;
+  bytes: number;
+  count: number;
+}
+
+export type FileCategory = Exclude<FileType, "folder">;
+
+export interface TypeStats {
+  total_bytes: number;
+  total_files: number;
+  by_category: { category: FileCategory; bytes: number; count: number }[];
+  by_extension: { extension?: string; category: FileCategory; bytes: number; count: number }[];
+}
+
+export interface AgeBucket {
+  label: string;  // "1w" | "1m" | "6m" | "1y" | "3y" | "older"
+  max_age_secs?: number;
+  bytes: number;
+  count: number;
+}
+
+export interface StaleDir {
+  path: string;
+  size: number;
+  file_count: number;
+  newest_modified_at: number;
+}
+
+export interface AgeAnalysis {
+  buckets: AgeBucket[];
+  unknown_bytes: number;
+  unknown_count: number;
+  stale_dirs: StaleDir[];
+}
+
+export interface OwnerUsage {
+  uid?: number;
+  username?: string;
+  bytes: number;
+  file_count: number;
+  dir_count: number;
+}
+
 export interface TreemapRect {
   id: string;
   node: FileNode;
@@ -213,6 +546,14 @@ export function formatSize(bytes: number): string {
   return `${(bytes / Math.pow(k, i)).toFixed(i > 0 ? 1 : 0)} ${units[i]}`;
 }
 
+export function formatDuration(ms: number): string {
+  const totalSecs = Math.round(ms / 1000);
+  if (totalSecs < 60) return `${totalSecs}s`;
+  const mins = Math.floor(totalSecs / 60);
+  if (mins < 60) return `${mins}m ${totalSecs % 60}s`;
+  return `${Math.floor(mins / 60)}h ${mins % 60}m`;
+}
+
 export function formatDate(timestamp: number): string {
   const date = new Date(timestamp * 1000);
   const now = new Date();